@@ -1,4 +1,12 @@
-use crate::frontend::parser::{Expression, Statement};
+use std::collections::HashMap;
+
+use crate::{
+    frontend::{
+        parser::{Expression, MatchArm, Operator, Pattern, Statement, Type},
+        scripts::ScriptKind,
+    },
+    util::diagnostic::Diagnostic,
+};
 
 pub enum GenType {
     Raw(String),
@@ -9,7 +17,7 @@ pub enum GenType {
         value_type: Option<String>,
     },
     AnonymousFunction {
-        params: Vec<String>,
+        signature: Signature,
     },
     FunctionCall {
         ident: String,
@@ -18,12 +26,12 @@ pub enum GenType {
     FunctionBody {
         local: bool,
         ident: String,
-        params: Vec<String>,
+        signature: Signature,
     },
     MethodBody {
         parent: String,
         ident: String,
-        params: Vec<String>,
+        signature: Signature,
     },
     ClassConstructor {
         ident: String,
@@ -47,20 +55,8 @@ impl GenType {
             GenType::LScope => "do".into(),
             GenType::RScope => "end".into(),
             GenType::LIf { expr } => format!("if {expr} then"),
-            GenType::AnonymousFunction { params } => {
-                let params_str = if !params.is_empty() {
-                    let mut params_str = params[0].clone();
-
-                    for param in params.iter().skip(1) {
-                        params_str += &format!(", {param}")
-                    }
-
-                    params_str
-                } else {
-                    String::new()
-                };
-
-                format!("function({params_str})")
+            GenType::AnonymousFunction { signature } => {
+                format!("function{}", signature.eval())
             }
             GenType::VariableDeclaration {
                 local,
@@ -102,43 +98,19 @@ impl GenType {
             GenType::MethodBody {
                 parent,
                 ident,
-                params,
+                signature,
             } => {
-                let params_str = if !params.is_empty() {
-                    let mut params_str = params[0].clone();
-
-                    for param in params.iter().skip(1) {
-                        params_str += &format!(", {param}")
-                    }
-
-                    params_str
-                } else {
-                    String::new()
-                };
-
-                format!("function {parent}:{ident}({params_str})")
+                format!("function {parent}:{ident}{}", signature.eval())
             }
             GenType::FunctionBody {
                 local,
                 ident,
-                params,
+                signature,
             } => {
-                let params_str = if !params.is_empty() {
-                    let mut params_str = params[0].clone();
-
-                    for param in params.iter().skip(1) {
-                        params_str += &format!(", {param}")
-                    }
-
-                    params_str
-                } else {
-                    String::new()
-                };
-
                 if *local {
-                    format!("local function {ident}({params_str})")
+                    format!("local function {ident}{}", signature.eval())
                 } else {
-                    format!("function {ident}({params_str})")
+                    format!("function {ident}{}", signature.eval())
                 }
             }
             GenType::ClassConstructor {
@@ -153,18 +125,134 @@ impl GenType {
     }
 }
 
+/// The parameter list of a function, shared by named functions, anonymous
+/// functions and methods.
+#[derive(Default)]
+pub struct Signature {
+    pub generics: Vec<String>,
+    pub params: Vec<String>,
+    /// Statements run before the function body, filling in default values
+    /// and collecting variadic arguments
+    pub prologue: Vec<String>,
+    pub return_type: Option<String>,
+}
+
+impl Signature {
+    pub fn eval(&self) -> String {
+        let generics = if self.generics.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", self.generics.join(", "))
+        };
+
+        match &self.return_type {
+            Some(return_type) => {
+                format!("{generics}({}): {return_type}", self.params.join(", "))
+            }
+            None => format!("{generics}({})", self.params.join(", ")),
+        }
+    }
+}
+
+/// How tightly an emitted expression binds, following Luau's precedence
+/// table. Anything that isn't an operation never needs parentheses.
+fn precedence(expr: &Expression) -> u8 {
+    match expr {
+        Expression::Or(..) => 1,
+        Expression::And(..) => 2,
+        Expression::Operation(operator, ..) => operator.precedence(),
+        Expression::Negate(_) => 7,
+        _ => u8::MAX,
+    }
+}
+
+/// Non-local values are emitted as `value :: T`, and `::` binds tighter than
+/// any binary operator, so those values have to be parenthesized.
+fn needs_cast_parens(value: &Expression) -> bool {
+    matches!(
+        value,
+        Expression::Operation(..) | Expression::And(..) | Expression::Or(..)
+    )
+}
+
+/// Makes assignments to `self.<field>` in the setter of `field` store the
+/// value in `self.__props`, instead of running the setter again.
+fn store_in_props(stmt: &mut Statement, field: &str) {
+    match stmt {
+        Statement::Scope(stmts) => {
+            for stmt in stmts {
+                store_in_props(stmt, field);
+            }
+        }
+        Statement::If { body, .. } => store_in_props(body, field),
+        Statement::Match { arms, .. } => {
+            for arm in arms {
+                store_in_props(&mut arm.body, field);
+            }
+        }
+        Statement::VariableAssignment {
+            ident: Expression::Indexing(object, member),
+            ..
+        } if matches!(
+            (object.as_ref(), member.as_ref()),
+            (Expression::Identifier(object), Expression::Identifier(member))
+                if object == "self" && member == field
+        ) =>
+        {
+            let field = member.clone();
+
+            **member =
+                Expression::Indexing(Box::new(Expression::Identifier("__props".into())), field);
+        }
+        _ => {}
+    }
+}
+
 pub struct CodeGen {
     pub src: String,
+    pub diagnostics: Vec<Diagnostic>,
     root_stmt: Statement,
     nest: usize,
+    enums: HashMap<String, Vec<(String, Vec<String>)>>,
+    functions: HashMap<String, Vec<Expression>>,
+    /// Constants with literal values, inlined where they're used
+    constants: HashMap<String, Expression>,
+    /// The argument passed to `require` for each imported path
+    pub requires: HashMap<String, String>,
+    exports: Vec<String>,
+    pub kind: Option<ScriptKind>,
 }
 
 impl CodeGen {
     pub fn new(stmt: Statement) -> Self {
         Self {
             src: String::new(),
+            diagnostics: vec![],
             root_stmt: stmt,
             nest: 0,
+            enums: HashMap::new(),
+            functions: HashMap::new(),
+            constants: HashMap::new(),
+            requires: HashMap::new(),
+            exports: vec![],
+            kind: None,
+        }
+    }
+
+    /// Creates a generator for a nested function body that shares the
+    /// declarations known to this one.
+    fn child(&self, stmt: Statement) -> Self {
+        Self {
+            src: String::new(),
+            diagnostics: vec![],
+            root_stmt: stmt,
+            nest: self.nest,
+            enums: self.enums.clone(),
+            functions: self.functions.clone(),
+            constants: self.constants.clone(),
+            requires: self.requires.clone(),
+            exports: vec![],
+            kind: self.kind,
         }
     }
 
@@ -174,20 +262,42 @@ impl CodeGen {
         self.src += &format!("{spaces}{code}\n");
     }
 
-    fn expr_to_value(expr: Expression) -> String {
+    /// Emits the operand of an operator, parenthesized when it binds less
+    /// tightly than `min`.
+    fn operand(&mut self, expr: Expression, min: u8) -> String {
+        if precedence(&expr) < min {
+            format!("({})", self.expr_to_value(expr))
+        } else {
+            self.expr_to_value(expr)
+        }
+    }
+
+    fn expr_to_value(&mut self, expr: Expression) -> String {
         match expr.clone() {
-            Expression::Identifier(ident) => ident,
-            Expression::Parameter { ident, .. } => Self::expr_to_value(*ident),
+            Expression::Identifier(ident) => match self.constants.get(&ident) {
+                Some(value) => self.expr_to_value(value.clone()),
+                None => ident,
+            },
+            Expression::Parameter { ident, .. } => self.expr_to_value(*ident),
             Expression::Char(char) => format!("\"{char}\""),
             Expression::String(string) => format!("\"{string}\""),
             Expression::Bool(bool) => format!("{bool}"),
             Expression::Number(number) => number.to_string(),
             Expression::FunctionCall { ident, args } | Expression::MethodCall { ident, args } => {
+                let args = if args
+                    .iter()
+                    .any(|arg| matches!(arg, Expression::NamedArgument { .. }))
+                {
+                    self.order_arguments(&ident, args)
+                } else {
+                    args
+                };
+
                 let args_str = if !args.is_empty() {
-                    let mut args_str = Self::expr_to_value(args[0].clone());
+                    let mut args_str = self.expr_to_value(args[0].clone());
 
                     for value in args.iter().skip(1) {
-                        args_str += &format!(", {}", Self::expr_to_value(value.clone()))
+                        args_str += &format!(", {}", self.expr_to_value(value.clone()))
                     }
 
                     args_str
@@ -195,57 +305,72 @@ impl CodeGen {
                     String::new()
                 };
 
-                format!("{}({args_str})", Self::expr_to_value(*ident))
+                format!("{}({args_str})", self.expr_to_value(*ident))
             }
             Expression::Indexing(l, r) => {
-                format!("{}.{}", Self::expr_to_value(*l), Self::expr_to_value(*r))
+                format!("{}.{}", self.expr_to_value(*l), self.member_to_value(*r))
             }
             Expression::And(l, r) => {
-                format!(
-                    "{} and {}",
-                    Self::expr_to_value(*l),
-                    Self::expr_to_value(*r)
-                )
+                format!("{} and {}", self.operand(*l, 2), self.operand(*r, 3))
             }
             Expression::Or(l, r) => {
-                format!("{} or {}", Self::expr_to_value(*l), Self::expr_to_value(*r))
+                format!("{} or {}", self.operand(*l, 1), self.operand(*r, 2))
             }
-            Expression::Function { params, stmt } => {
-                let mut params_str = vec![];
+            Expression::Operation(operator, l, r) => {
+                let symbol = match operator {
+                    Operator::Ne => "~=",
+                    operator => operator.symbol(),
+                };
 
-                if !params.is_empty() {
-                    let param_str = Self::expr_to_value_with_type(
-                        &Self::new(Statement::Scope(vec![])),
-                        params[0].clone(),
-                    );
-                    params_str.push(if let Some(expected_type) = param_str.0 {
-                        format!("{}: {expected_type}", param_str.1)
-                    } else {
-                        param_str.1
-                    });
-                }
+                // '^' is the only right associative operator
+                let precedence = operator.precedence();
+                let (l_min, r_min) = if operator == Operator::Pow {
+                    (precedence + 1, precedence)
+                } else {
+                    (precedence, precedence + 1)
+                };
 
-                let mut inner = Self::new(*stmt.clone());
-                inner.write(GenType::AnonymousFunction { params: params_str });
+                format!(
+                    "{} {symbol} {}",
+                    self.operand(*l, l_min),
+                    self.operand(*r, r_min)
+                )
+            }
+            // A nested minus is parenthesized too, `--` would start a comment
+            Expression::Negate(expr) => format!("-{}", self.operand(*expr, 8)),
+            Expression::Function {
+                generics,
+                params,
+                return_type,
+                stmt,
+            } => {
+                let signature = self.gen_signature(generics, params, return_type);
 
-                if let Statement::Scope(scope) = *stmt {
-                    for stmt in scope {
-                        inner.gen_statement(stmt.clone());
-                    }
-                } else {
-                    inner.gen_statement(*stmt);
-                }
+                let mut inner = self.child(*stmt.clone());
+                inner.gen_function(GenType::AnonymousFunction { signature }, *stmt);
+
+                self.diagnostics.append(&mut inner.diagnostics);
+
+                inner.src.trim().into()
+            }
+            Expression::Match { value, arms } => {
+                let mut inner = self.child(Statement::Scope(vec![]));
 
-                inner.write(GenType::RScope);
+                inner.write(GenType::Raw("(function()".into()));
+                inner.nest += 1;
+                inner.gen_match(*value, arms);
+                inner.nest -= 1;
+                inner.write(GenType::Raw("end)()".into()));
 
-                inner.src
+                self.diagnostics.append(&mut inner.diagnostics);
+
+                inner.src.trim().into()
             }
             _ => unimplemented!("{expr:?}"),
         }
     }
 
-    #[allow(clippy::only_used_in_recursion)]
-    fn expr_to_value_with_type(&self, expr: Expression) -> (Option<String>, String) {
+    fn expr_to_value_with_type(&mut self, expr: Expression) -> (Option<String>, String) {
         let type_str: Option<String> = match expr.clone() {
             Expression::Identifier(_) => None,
             Expression::Indexing(_, _) => None,
@@ -255,254 +380,2122 @@ impl CodeGen {
             Expression::MethodCall { .. } => None,
             Expression::And(_, r) => self.expr_to_value_with_type(*r).0,
             Expression::Or(_, r) => self.expr_to_value_with_type(*r).0,
+            Expression::Operation(operator, _, _) if operator.is_comparison() => {
+                Some("boolean".into())
+            }
+            Expression::Operation(_, l, r) => {
+                let l = self.expr_to_value_with_type(*l).0;
+                let r = self.expr_to_value_with_type(*r).0;
+
+                if l.as_deref() == Some("number") && r.as_deref() == Some("number") {
+                    l
+                } else {
+                    None
+                }
+            }
+            Expression::Negate(expr) => self.expr_to_value_with_type(*expr).0,
             Expression::Parameter { expected_type, .. } => {
-                (*expected_type).map(Self::expr_to_value)
+                expected_type.map(|expected_type| expected_type.to_string())
             }
             Expression::Char(_) | Expression::String(_) => Some("string".into()),
             Expression::Number(_) => Some("number".into()),
+            Expression::EnumVariant { .. }
+            | Expression::Match { .. }
+            | Expression::NamedArgument { .. } => None,
             Expression::ClassBody { .. } => todo!(),
         };
 
-        let value_str = Self::expr_to_value(expr);
+        let value_str = self.expr_to_value(expr);
 
         (type_str, value_str)
     }
 
-    fn indexing_to_value(l: Expression, r: Expression) -> String {
+    fn indexing_to_value(&mut self, l: Expression, r: Expression) -> String {
         if matches!(r, Expression::MethodCall { .. }) {
-            format!("{}:{}", Self::expr_to_value(l), Self::expr_to_value(r))
+            format!("{}:{}", self.expr_to_value(l), self.member_to_value(r))
         } else {
-            format!("{}.{}", Self::expr_to_value(l), Self::expr_to_value(r))
+            format!("{}.{}", self.expr_to_value(l), self.member_to_value(r))
         }
     }
 
-    #[allow(clippy::only_used_in_recursion)]
-    fn gen_statement(&mut self, stmt: Statement) {
-        match stmt.clone() {
-            Statement::Luau(code) => {
-                self.write(GenType::Raw(code));
+    /// Emits the right side of `a.b`, where identifiers are member names
+    /// rather than variables, so constants aren't inlined into them.
+    fn member_to_value(&mut self, expr: Expression) -> String {
+        match expr {
+            Expression::Identifier(ident) => ident,
+            Expression::Indexing(l, r) => {
+                format!("{}.{}", self.member_to_value(*l), self.member_to_value(*r))
             }
-            Statement::Return(expr) => self.write(GenType::Return {
-                value: Self::expr_to_value(expr),
-            }),
-            Statement::If { expr, body } => {
-                self.write(GenType::LIf {
-                    expr: Self::expr_to_value(expr),
-                });
+            Expression::FunctionCall { ident, args } | Expression::MethodCall { ident, args } => {
+                let args = args
+                    .into_iter()
+                    .map(|arg| self.expr_to_value(arg))
+                    .collect::<Vec<_>>()
+                    .join(", ");
 
-                self.nest += 1;
+                format!("{}({args})", self.member_to_value(*ident))
+            }
+            expr => self.expr_to_value(expr),
+        }
+    }
 
-                if let Statement::Scope(statements) = *body {
-                    for stmt in statements {
-                        self.gen_statement(stmt);
-                    }
-                } else {
-                    self.gen_statement(*body);
-                }
+    fn ident_to_value(&mut self, ident: Expression) -> String {
+        match ident {
+            Expression::Identifier(ident) => ident,
+            Expression::Indexing(l, r) => self.indexing_to_value(*l, *r),
+            _ => panic!("{ident:?} can't be converted to identifier"),
+        }
+    }
 
-                self.nest -= 1;
+    fn gen_function(&mut self, header: GenType, stmt: Statement) {
+        let prologue = match &header {
+            GenType::FunctionBody { signature, .. }
+            | GenType::MethodBody { signature, .. }
+            | GenType::AnonymousFunction { signature } => signature.prologue.clone(),
+            _ => vec![],
+        };
 
-                self.write(GenType::RScope);
+        self.write(header);
+        self.nest += 1;
+
+        for line in prologue {
+            self.write(GenType::Raw(line));
+        }
+
+        self.nest -= 1;
+        self.gen_body(stmt);
+    }
+
+    fn gen_body(&mut self, stmt: Statement) {
+        if let Statement::Scope(scope) = stmt {
+            // Functions declared in the body take named arguments within it
+            let functions = self.functions.clone();
+
+            for stmt in &scope {
+                self.declare(stmt);
             }
-            Statement::Scope(statements) => {
-                self.write(GenType::LScope);
-                self.nest += 1;
 
-                for stmt in statements {
-                    self.gen_statement(stmt.clone());
+            self.nest += 1;
+
+            for stmt in scope {
+                self.gen_statement(stmt.clone());
+            }
+
+            self.nest -= 1;
+            self.functions = functions;
+        } else {
+            self.gen_statement(stmt);
+        }
+
+        self.write(GenType::RScope);
+    }
+
+    /// Emits the `__getters` and `__setters` tables backing a class that
+    /// declares accessors or property observers, along with the `__index` and
+    /// `__newindex` metamethods that dispatch to them. Observed fields and
+    /// fields with setters are stored in `self.__props` so every write goes
+    /// through `__newindex`. A setter stores the value itself, by assigning
+    /// the field it sets.
+    fn gen_class_accessors(
+        &mut self,
+        class: &str,
+        class_generics: &[String],
+        getters: Vec<Statement>,
+        setters: Vec<Statement>,
+        observers: Vec<Statement>,
+    ) {
+        for table in ["__getters", "__setters"] {
+            self.write(GenType::VariableDeclaration {
+                local: false,
+                ident: format!("{class}.{table}"),
+                value: "{}".into(),
+                value_type: None,
+            });
+        }
+
+        for getter in getters {
+            if let Statement::VariableDeclaration {
+                ident,
+                value:
+                    Expression::Function {
+                        return_type, stmt, ..
+                    },
+                ..
+            } = getter
+            {
+                let ident = self.expr_to_value(ident);
+                let signature = self.gen_signature(class_generics.to_vec(), vec![], return_type);
+
+                self.gen_function(
+                    GenType::MethodBody {
+                        parent: format!("{class}.__getters"),
+                        ident,
+                        signature,
+                    },
+                    *stmt,
+                );
+            }
+        }
+
+        let setter_names = setters
+            .iter()
+            .filter_map(|setter| match setter {
+                Statement::VariableDeclaration { ident, .. } => {
+                    Some(self.expr_to_value(ident.clone()))
                 }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
 
-                self.nest -= 1;
-                self.write(GenType::RScope);
+        for setter in setters {
+            if let Statement::VariableDeclaration {
+                ident,
+                value:
+                    Expression::Function {
+                        generics,
+                        params,
+                        return_type,
+                        mut stmt,
+                    },
+                ..
+            } = setter
+            {
+                let ident = self.expr_to_value(ident);
+                let signature =
+                    self.gen_signature([class_generics, &generics].concat(), params, return_type);
+
+                store_in_props(&mut stmt, &ident);
+
+                self.gen_function(
+                    GenType::MethodBody {
+                        parent: format!("{class}.__setters"),
+                        ident,
+                        signature,
+                    },
+                    *stmt,
+                );
             }
-            Statement::ClassConstructor { ident, body } => {
-                let ident_str = Self::expr_to_value(ident.clone());
+        }
 
-                self.write(GenType::VariableDeclaration {
-                    local: true,
-                    ident: ident_str.clone(),
-                    value: "{}".into(),
-                    value_type: None,
-                });
+        for observer in observers {
+            if let Statement::VariableDeclaration {
+                ident,
+                value: Expression::Function { params, stmt, .. },
+                ..
+            } = observer
+            {
+                let ident = self.expr_to_value(ident);
 
-                self.write(GenType::FunctionBody {
-                    local: false,
-                    ident: format!("{ident_str}.new"),
-                    params: vec![],
+                if setter_names.contains(&ident) {
+                    self.diagnostics.push(Diagnostic::error(format!(
+                        "'{class}.{ident}' can't have both a setter and a didSet observer"
+                    )));
+
+                    continue;
+                }
+
+                self.write(GenType::MethodBody {
+                    parent: format!("{class}.__setters"),
+                    ident: ident.clone(),
+                    signature: Signature {
+                        params: vec!["value".into()],
+                        ..Default::default()
+                    },
                 });
 
                 self.nest += 1;
 
+                if let Some(old) = params.into_iter().next() {
+                    let old = self.expr_to_value(old);
+
+                    self.write(GenType::VariableDeclaration {
+                        local: true,
+                        ident: old,
+                        value: format!("self.__props.{ident}"),
+                        value_type: None,
+                    });
+                }
+
                 self.write(GenType::VariableDeclaration {
-                    local: true,
-                    ident: String::from("self"),
-                    value: String::from("{}"),
+                    local: false,
+                    ident: format!("self.__props.{ident}"),
+                    value: "value".into(),
                     value_type: None,
                 });
 
-                if let Expression::ClassBody {
-                    properties,
-                    methods,
-                } = body
-                {
-                    for prop in properties {
-                        if let Statement::VariableDeclaration { ident, value } = prop {
-                            let (value_type_str, value_str) = self.expr_to_value_with_type(value);
-
-                            self.write(GenType::VariableDeclaration {
-                                local: false,
-                                ident: match ident {
-                                    Expression::Identifier(ident) => format!("self.{ident}"),
-                                    Expression::Indexing(l, r) => Self::indexing_to_value(*l, *r),
-                                    _ => panic!("{ident:?} can't be converted to identifier"),
-                                },
-                                value: value_str,
-                                value_type: value_type_str,
-                            })
-                        } else {
-                            todo!()
-                        }
-                    }
+                self.nest -= 1;
 
-                    for method in methods {
-                        if let Statement::VariableDeclaration { ident, value } = method {
-                            if let Expression::Function { params, stmt } = value {
-                                let mut params_str = vec![];
-
-                                if !params.is_empty() {
-                                    let param_str = self.expr_to_value_with_type(params[0].clone());
-                                    params_str.push(if let Some(expected_type) = param_str.0 {
-                                        format!("{}: {expected_type}", param_str.1)
-                                    } else {
-                                        param_str.1
-                                    });
-                                }
-
-                                self.write(GenType::MethodBody {
-                                    parent: "self".into(),
-                                    ident: match ident {
-                                        Expression::Identifier(ident) => ident,
-                                        Expression::Indexing(l, r) => {
-                                            Self::indexing_to_value(*l, *r)
-                                        }
-                                        _ => panic!("{ident:?} can't be converted to identifier"),
-                                    },
-                                    params: params_str,
-                                });
-
-                                if let Statement::Scope(scope) = *stmt {
-                                    self.nest += 1;
-
-                                    for stmt in scope {
-                                        self.gen_statement(stmt.clone());
-                                    }
-
-                                    self.nest -= 1;
-                                } else {
-                                    self.gen_statement(*stmt);
-                                }
-
-                                self.write(GenType::RScope);
-                            } else {
-                                panic!()
-                            }
-                        } else {
-                            todo!()
-                        }
-                    }
-                } else {
-                    panic!("{body:?} is not a valid class body")
-                }
+                self.gen_body(*stmt);
+            }
+        }
 
-                self.write(GenType::Return {
-                    value: String::from("self"),
-                });
+        for line in [
+            format!("function {class}.__meta.__index(self, key)"),
+            format!("    local getter = {class}.__getters[key]"),
+            "    if getter then".into(),
+            "        return getter(self)".into(),
+            "    end".into(),
+            "    return self.__props[key]".into(),
+            "end".into(),
+            format!("function {class}.__meta.__newindex(self, key, value)"),
+            format!("    local setter = {class}.__setters[key]"),
+            "    if setter then".into(),
+            "        setter(self, value)".into(),
+            "    else".into(),
+            "        rawset(self, key, value)".into(),
+            "    end".into(),
+            "end".into(),
+        ] {
+            self.write(GenType::Raw(line));
+        }
+    }
 
-                self.nest -= 1;
+    fn gen_class_metamethods(
+        &mut self,
+        class: &str,
+        class_generics: &[String],
+        metamethods: Vec<Statement>,
+    ) {
+        for metamethod in metamethods {
+            if let Statement::VariableDeclaration {
+                ident,
+                value:
+                    Expression::Function {
+                        generics,
+                        params,
+                        return_type,
+                        stmt,
+                    },
+                ..
+            } = metamethod
+            {
+                let ident = self.expr_to_value(ident);
+                let metamethod = match ident.as_str() {
+                    "-" if params.is_empty() => "__unm",
+                    "+" => "__add",
+                    "-" => "__sub",
+                    "*" => "__mul",
+                    "/" => "__div",
+                    "%" => "__mod",
+                    "^" => "__pow",
+                    "==" => "__eq",
+                    "<" => "__lt",
+                    "<=" => "__le",
+                    "tostring" => "__tostring",
+                    "call" => "__call",
+                    "len" => "__len",
+                    _ => panic!("'{ident}' is not a valid metamethod"),
+                };
 
-                self.write(GenType::RScope);
+                let signature =
+                    self.gen_signature([class_generics, &generics].concat(), params, return_type);
+
+                self.gen_function(
+                    GenType::MethodBody {
+                        parent: format!("{class}.__meta"),
+                        ident: metamethod.into(),
+                        signature,
+                    },
+                    *stmt,
+                );
             }
-            Statement::VariableDeclaration { ident, value }
-            | Statement::VariableAssignment { ident, value } => {
-                let local = matches!(stmt, Statement::VariableDeclaration { .. });
+        }
+    }
 
-                if let Expression::Identifier(value_ident) = value.clone() {
-                    self.write(GenType::VariableDeclaration {
-                        local,
-                        ident: match ident {
-                            Expression::Identifier(ident) => ident,
-                            Expression::Indexing(l, r) => Self::indexing_to_value(*l, *r),
-                            _ => panic!("{ident:?} can't be converted to identifier"),
-                        },
-                        value: value_ident.to_owned(),
-                        value_type: None,
-                    });
-                } else if let Expression::Function { params, stmt } = value {
-                    let mut params_str = vec![];
+    /// Emits the parameter list shared by every kind of function.
+    fn gen_signature(
+        &mut self,
+        generics: Vec<String>,
+        params: Vec<Expression>,
+        return_type: Option<Type>,
+    ) -> Signature {
+        let mut signature = Signature {
+            generics,
+            return_type: return_type.map(|return_type| return_type.to_string()),
+            ..Default::default()
+        };
 
-                    if !params.is_empty() {
-                        let param_str = self.expr_to_value_with_type(params[0].clone());
-                        params_str.push(if let Some(expected_type) = param_str.0 {
-                            format!("{}: {expected_type}", param_str.1)
-                        } else {
-                            param_str.1
-                        });
-                    }
+        for param in params {
+            let Expression::Parameter {
+                ident,
+                expected_type,
+                default,
+                variadic,
+            } = param
+            else {
+                panic!("{param:?} is not a parameter")
+            };
+
+            // Parameters shadow constants with the same name
+            if let Expression::Identifier(ident) = ident.as_ref() {
+                self.constants.remove(ident);
+            }
 
-                    for param in params.iter().skip(1) {
-                        let param_str = self.expr_to_value_with_type(param.clone());
+            let ident = self.expr_to_value(*ident);
+            let mut param_type = expected_type;
 
-                        params_str.push(if let Some(expected_type) = param_str.0 {
-                            format!("{}: {expected_type}", param_str.1)
-                        } else {
-                            param_str.1
-                        });
-                    }
+            if variadic {
+                signature.params.push(match param_type {
+                    Some(param_type) => format!("...: {param_type}"),
+                    None => "...".into(),
+                });
+                signature
+                    .prologue
+                    .push(format!("local {ident} = {{ ... }}"));
 
-                    self.write(GenType::FunctionBody {
-                        local,
-                        ident: Self::expr_to_value(ident),
-                        params: params_str,
-                    });
+                continue;
+            }
 
-                    if let Statement::Scope(scope) = *stmt {
-                        self.nest += 1;
+            if let Some(default) = *default {
+                let (default_type, default) = self.expr_to_value_with_type(default);
+
+                param_type = param_type
+                    .or(default_type.map(|ident| Type::Named {
+                        ident,
+                        generics: vec![],
+                    }))
+                    .map(|param_type| Type::Optional(Box::new(param_type)));
+                signature
+                    .prologue
+                    .push(format!("if {ident} == nil then {ident} = {default} end"));
+            }
+
+            signature.params.push(match param_type {
+                Some(param_type) => format!("{ident}: {param_type}"),
+                None => ident,
+            });
+        }
+
+        signature
+    }
+
+    /// Moves named arguments into the position of the parameter they name,
+    /// passing `nil` for skipped parameters so their defaults apply.
+    fn order_arguments(&mut self, ident: &Expression, args: Vec<Expression>) -> Vec<Expression> {
+        let function = self.expr_to_value(ident.clone());
+
+        let Some(params) = self.functions.get(&function).cloned() else {
+            self.diagnostics.push(Diagnostic::error(format!(
+                "can't pass named arguments to '{function}', only functions declared with 'let' in this file take them"
+            )));
+
+            return args
+                .into_iter()
+                .filter(|arg| !matches!(arg, Expression::NamedArgument { .. }))
+                .collect();
+        };
 
-                        for stmt in scope {
-                            self.gen_statement(stmt.clone());
+        let params = params
+            .into_iter()
+            .map(|param| match param {
+                Expression::Parameter {
+                    ident, variadic, ..
+                } => (self.member_to_value(*ident), variadic),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+
+        let mut ordered: Vec<Option<Expression>> = vec![];
+        let mut position = 0;
+
+        for arg in args {
+            let index = match arg {
+                Expression::NamedArgument { ref ident, .. } => {
+                    let name = self.member_to_value(*ident.clone());
+
+                    match params.iter().position(|(param, _)| *param == name) {
+                        Some(index) if params[index].1 => {
+                            self.diagnostics.push(Diagnostic::error(format!(
+                                "variadic parameter '{name}' of '{function}' can't be passed by name"
+                            )));
+
+                            continue;
                         }
+                        Some(index) => index,
+                        None => {
+                            self.diagnostics.push(Diagnostic::error(format!(
+                                "'{function}' has no parameter named '{name}'"
+                            )));
 
-                        self.nest -= 1;
-                    } else {
-                        self.gen_statement(*stmt);
+                            continue;
+                        }
                     }
+                }
+                _ => {
+                    position += 1;
+                    position - 1
+                }
+            };
 
-                    self.write(GenType::RScope);
-                } else {
-                    let (type_str, value_str) = self.expr_to_value_with_type(value);
+            let value = match arg {
+                Expression::NamedArgument { value, .. } => *value,
+                arg => arg,
+            };
 
-                    self.write(GenType::VariableDeclaration {
-                        local,
-                        ident: match ident {
-                            Expression::Identifier(ident) => ident,
-                            Expression::Indexing(l, r) => Self::indexing_to_value(*l, *r),
-                            _ => panic!("{ident:?} can't be converted to identifier"),
-                        },
-                        value: value_str,
-                        value_type: type_str,
-                    });
-                }
+            if ordered.len() <= index {
+                ordered.resize(index + 1, None);
             }
-        }
-    }
 
-    pub fn run(&mut self) {
-        if let Statement::Scope(scope) = self.root_stmt.clone() {
-            for stmt in scope {
-                self.gen_statement(stmt.clone());
+            if ordered[index].is_some() {
+                self.diagnostics.push(Diagnostic::error(format!(
+                    "parameter '{}' of '{function}' is passed more than once",
+                    params.get(index).map_or("?", |(param, _)| param.as_str())
+                )));
             }
-        } else {
-            panic!("Root stmt must be a scope");
+
+            ordered[index] = Some(value);
         }
+
+        ordered
+            .into_iter()
+            .map(|arg| arg.unwrap_or_else(|| Expression::Identifier("nil".into())))
+            .collect()
+    }
+
+    fn param_to_type(&mut self, param: Expression) -> (String, Option<String>) {
+        let (param_type, param_str) = self.expr_to_value_with_type(param);
+
+        (param_str, param_type)
+    }
+
+    fn gen_interface(
+        &mut self,
+        ident: Expression,
+        properties: Vec<Expression>,
+        methods: Vec<Statement>,
+    ) {
+        let ident = self.expr_to_value(ident);
+        let export = if self.nest == 0 { "export " } else { "" };
+
+        self.write(GenType::Raw(format!("{export}type {ident} = {{")));
+        self.nest += 1;
+
+        for property in properties {
+            let (property, property_type) = self.param_to_type(property);
+            let property_type = property_type.unwrap_or_else(|| "any".into());
+
+            self.write(GenType::Raw(format!("{property}: {property_type},")));
+        }
+
+        for method in methods {
+            if let Statement::VariableDeclaration {
+                ident: method,
+                value:
+                    Expression::Function {
+                        generics,
+                        params,
+                        return_type,
+                        ..
+                    },
+                ..
+            } = method
+            {
+                let method = self.expr_to_value(method);
+                let method_type = self.method_type(&ident, generics, params, return_type);
+
+                self.write(GenType::Raw(format!("{method}: {method_type},")));
+            }
+        }
+
+        self.nest -= 1;
+        self.write(GenType::Raw("}".into()));
+    }
+
+    /// Emits the function type of a method called on `owner`.
+    fn method_type(
+        &mut self,
+        owner: &str,
+        generics: Vec<String>,
+        params: Vec<Expression>,
+        return_type: Option<Type>,
+    ) -> String {
+        let mut params_str = vec![format!("self: {owner}")];
+
+        for param in params {
+            let (param, param_type) = self.param_to_type(param);
+            let param_type = param_type.unwrap_or_else(|| "any".into());
+
+            params_str.push(format!("{param}: {param_type}"));
+        }
+
+        let generics = if generics.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", generics.join(", "))
+        };
+        let return_type = return_type
+            .map(|return_type| return_type.grouped())
+            .unwrap_or_else(|| "()".into());
+
+        format!("{generics}({}) -> {return_type}", params_str.join(", "))
+    }
+
+    /// Emits the type of a generic class instance, so the type parameters of
+    /// the class can be named outside of it.
+    fn gen_class_type(&mut self, ident: &str, generics: &[String], body: &Expression) {
+        let Expression::ClassBody {
+            properties,
+            methods,
+            getters,
+            ..
+        } = body.clone()
+        else {
+            return;
+        };
+
+        let export = if self.nest == 0 { "export " } else { "" };
+        let owner = format!("{ident}<{}>", generics.join(", "));
+
+        self.write(GenType::Raw(format!("{export}type {owner} = {{")));
+        self.nest += 1;
+
+        for property in properties {
+            if let Statement::VariableDeclaration {
+                ident,
+                value_type,
+                value,
+            } = property
+            {
+                let ident = self.ident_to_value(ident);
+                let property_type = match value_type {
+                    Some(value_type) => value_type.to_string(),
+                    None => self
+                        .expr_to_value_with_type(value)
+                        .0
+                        .unwrap_or_else(|| "any".into()),
+                };
+
+                self.write(GenType::Raw(format!("{ident}: {property_type},")));
+            }
+        }
+
+        for getter in getters {
+            if let Statement::VariableDeclaration {
+                ident,
+                value: Expression::Function { return_type, .. },
+                ..
+            } = getter
+            {
+                let ident = self.ident_to_value(ident);
+                let property_type = return_type
+                    .map(|return_type| return_type.to_string())
+                    .unwrap_or_else(|| "any".into());
+
+                self.write(GenType::Raw(format!("{ident}: {property_type},")));
+            }
+        }
+
+        for method in methods {
+            if let Statement::VariableDeclaration {
+                ident,
+                value:
+                    Expression::Function {
+                        generics,
+                        params,
+                        return_type,
+                        ..
+                    },
+                ..
+            } = method
+            {
+                let ident = self.ident_to_value(ident);
+                let method_type = self.method_type(&owner, generics, params, return_type);
+
+                self.write(GenType::Raw(format!("{ident}: {method_type},")));
+            }
+        }
+
+        self.nest -= 1;
+        self.write(GenType::Raw("}".into()));
+    }
+
+    /// Registers the declarations a statement introduces so they can be used
+    /// before the statement itself is generated.
+    fn declare(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::Enum {
+                ident: Expression::Identifier(ident),
+                variants,
+            } => {
+                let variants = variants
+                    .iter()
+                    .filter_map(|variant| match variant {
+                        Expression::EnumVariant { ident, fields } => Some((
+                            self.expr_to_value(*ident.clone()),
+                            fields
+                                .iter()
+                                .map(|field| self.expr_to_value(field.clone()))
+                                .collect(),
+                        )),
+                        _ => None,
+                    })
+                    .collect();
+
+                self.enums.insert(ident.clone(), variants);
+            }
+            Statement::VariableDeclaration {
+                ident: Expression::Identifier(ident),
+                value: Expression::Function { params, .. },
+                ..
+            } => {
+                self.functions.insert(ident.clone(), params.clone());
+            }
+            Statement::Export(stmt) => self.declare(stmt),
+            _ => {}
+        }
+    }
+
+    fn gen_enum(&mut self, ident: Expression, variants: Vec<Expression>) {
+        let ident = self.expr_to_value(ident);
+        let export = if self.nest == 0 { "export " } else { "" };
+        let mut variant_types = vec![];
+        let mut constructors = vec![];
+
+        for variant in variants {
+            let Expression::EnumVariant {
+                ident: variant,
+                fields,
+            } = variant
+            else {
+                continue;
+            };
+
+            let variant = self.expr_to_value(*variant);
+            let signature = self.gen_signature(vec![], fields.clone(), None);
+            let mut members = vec![format!("tag: \"{variant}\"")];
+            let mut values = vec![format!("tag = \"{variant}\"")];
+
+            for field in fields {
+                let (field, field_type) = self.param_to_type(field);
+
+                if field == "tag" {
+                    self.diagnostics.push(Diagnostic::error(format!(
+                        "variant '{ident}.{variant}' can't have a field named 'tag'"
+                    )));
+                }
+
+                members.push(format!(
+                    "{field}: {}",
+                    field_type.unwrap_or_else(|| "any".into())
+                ));
+                values.push(format!("{field} = {field}"));
+            }
+
+            variant_types.push(format!("{{ {} }}", members.join(", ")));
+            constructors.push((variant, signature, values));
+        }
+
+        self.write(GenType::Raw(format!(
+            "{export}type {ident} = {}",
+            variant_types.join(" | ")
+        )));
+        self.write(GenType::Raw(format!("local {ident} = table.freeze({{")));
+        self.nest += 1;
+
+        for (variant, signature, values) in constructors {
+            if signature.params.is_empty() {
+                self.write(GenType::Raw(format!(
+                    "{variant} = table.freeze({{ {} }}),",
+                    values.join(", ")
+                )));
+            } else {
+                self.write(GenType::Raw(format!(
+                    "{variant} = {}",
+                    GenType::AnonymousFunction { signature }.eval()
+                )));
+                self.nest += 1;
+                self.write(GenType::Return {
+                    value: format!("table.freeze({{ {} }})", values.join(", ")),
+                });
+                self.nest -= 1;
+                self.write(GenType::Raw("end,".into()));
+            }
+        }
+
+        self.nest -= 1;
+        self.write(GenType::Raw("})".into()));
+    }
+
+    /// Finds the enum declaring a variant, returning the enum name and the
+    /// variant's field names.
+    fn find_variant(
+        &self,
+        enum_ident: Option<&str>,
+        ident: &str,
+    ) -> Result<(String, Vec<String>), String> {
+        let mut found = self
+            .enums
+            .iter()
+            .filter(|(name, _)| enum_ident.is_none_or(|enum_ident| enum_ident == *name))
+            .filter_map(|(name, variants)| {
+                variants
+                    .iter()
+                    .find(|(variant, _)| variant == ident)
+                    .map(|(_, fields)| (name.clone(), fields.clone()))
+            })
+            .collect::<Vec<_>>();
+
+        match (found.len(), enum_ident) {
+            (1, _) => Ok(found.remove(0)),
+            (0, Some(enum_ident)) if !self.enums.contains_key(enum_ident) => {
+                Err(format!("unknown enum '{enum_ident}'"))
+            }
+            (0, Some(enum_ident)) => Err(format!("'{enum_ident}' has no variant '{ident}'")),
+            (0, None) => Err(format!("unknown enum variant '{ident}'")),
+            _ => {
+                found.sort();
+
+                Err(format!(
+                    "variant '{ident}' is ambiguous, it is declared by {}",
+                    found
+                        .iter()
+                        .map(|(name, _)| format!("'{name}'"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            }
+        }
+    }
+
+    fn is_irrefutable(&self, pattern: &Pattern) -> bool {
+        match pattern {
+            Pattern::Wildcard => true,
+            Pattern::Identifier(ident) => self.find_variant(None, ident).is_err(),
+            Pattern::Or(alternatives) => alternatives
+                .iter()
+                .any(|alternative| self.is_irrefutable(alternative)),
+            Pattern::Variant { .. }
+            | Pattern::Literal(_)
+            | Pattern::Range { .. }
+            | Pattern::Table(_)
+            | Pattern::Array { .. } => false,
+        }
+    }
+
+    /// Replaces references to match bindings in a guard with the paths they
+    /// are bound to, since guards are evaluated before the bindings exist.
+    fn substitute_bindings(expr: Expression, bindings: &[(String, String)]) -> Expression {
+        let substitute =
+            |expr: Box<Expression>| Box::new(Self::substitute_bindings(*expr, bindings));
+
+        match expr {
+            Expression::Identifier(ident) => Expression::Identifier(
+                bindings
+                    .iter()
+                    .find(|(binding, _)| *binding == ident)
+                    .map(|(_, path)| path.clone())
+                    .unwrap_or(ident),
+            ),
+            Expression::Indexing(l, r) => {
+                let r = match *r {
+                    Expression::FunctionCall { ident, args } => Expression::FunctionCall {
+                        ident,
+                        args: args
+                            .into_iter()
+                            .map(|arg| Self::substitute_bindings(arg, bindings))
+                            .collect(),
+                    },
+                    r => r,
+                };
+
+                Expression::Indexing(substitute(l), Box::new(r))
+            }
+            Expression::FunctionCall { ident, args } => Expression::FunctionCall {
+                ident: substitute(ident),
+                args: args
+                    .into_iter()
+                    .map(|arg| Self::substitute_bindings(arg, bindings))
+                    .collect(),
+            },
+            Expression::MethodCall { ident, args } => Expression::MethodCall {
+                ident: substitute(ident),
+                args: args
+                    .into_iter()
+                    .map(|arg| Self::substitute_bindings(arg, bindings))
+                    .collect(),
+            },
+            Expression::And(l, r) => Expression::And(substitute(l), substitute(r)),
+            Expression::Or(l, r) => Expression::Or(substitute(l), substitute(r)),
+            Expression::Operation(operator, l, r) => {
+                Expression::Operation(operator, substitute(l), substitute(r))
+            }
+            Expression::Negate(expr) => Expression::Negate(substitute(expr)),
+            expr => expr,
+        }
+    }
+
+    /// Turns a pattern into the condition testing `path` against it, `None`
+    /// when the pattern always matches, and the bindings it introduces.
+    fn pattern_to_condition(
+        &mut self,
+        pattern: Pattern,
+        path: &str,
+    ) -> (Option<String>, Vec<(String, String)>) {
+        match pattern {
+            Pattern::Wildcard => (None, vec![]),
+            Pattern::Identifier(ident) => {
+                if self.find_variant(None, &ident).is_ok() {
+                    (Some(format!("{path}.tag == \"{ident}\"")), vec![])
+                } else {
+                    (None, vec![(ident, path.into())])
+                }
+            }
+            Pattern::Variant {
+                enum_ident,
+                ident,
+                fields,
+            } => {
+                let declared = match self.find_variant(enum_ident.as_deref(), &ident) {
+                    Ok((_, declared)) => declared,
+                    Err(message) => {
+                        self.diagnostics.push(Diagnostic::error(message));
+
+                        return (Some("false".into()), vec![]);
+                    }
+                };
+
+                if !fields.is_empty() && fields.len() != declared.len() {
+                    self.diagnostics.push(Diagnostic::error(format!(
+                        "variant '{ident}' has {} field(s) but the pattern has {}",
+                        declared.len(),
+                        fields.len()
+                    )));
+                }
+
+                let mut conditions = vec![format!("{path}.tag == \"{ident}\"")];
+                let mut bindings = vec![];
+
+                for (field, name) in fields.into_iter().zip(declared) {
+                    let (condition, mut field_bindings) =
+                        self.pattern_to_condition(field, &format!("{path}.{name}"));
+
+                    conditions.extend(condition);
+                    bindings.append(&mut field_bindings);
+                }
+
+                (Some(conditions.join(" and ")), bindings)
+            }
+            Pattern::Literal(literal) => {
+                let literal = self.expr_to_value(literal);
+
+                (Some(format!("{path} == {literal}")), vec![])
+            }
+            Pattern::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                let start = self.expr_to_value(start);
+                let end = self.expr_to_value(end);
+                let operator = if inclusive { "<=" } else { "<" };
+
+                (
+                    Some(format!(
+                        "type({path}) == \"number\" and {path} >= {start} and {path} {operator} {end}"
+                    )),
+                    vec![],
+                )
+            }
+            Pattern::Table(fields) => {
+                let mut conditions = vec![format!("type({path}) == \"table\"")];
+                let mut bindings = vec![];
+
+                for (field, pattern) in fields {
+                    let field_path = format!("{path}.{field}");
+                    let (condition, mut field_bindings) = match pattern {
+                        Some(pattern) => self.pattern_to_condition(pattern, &field_path),
+                        None => (None, vec![(field, field_path.clone())]),
+                    };
+
+                    conditions.push(condition.unwrap_or_else(|| format!("{field_path} ~= nil")));
+                    bindings.append(&mut field_bindings);
+                }
+
+                (Some(conditions.join(" and ")), bindings)
+            }
+            Pattern::Array { items, rest } => {
+                let len = items.len();
+                let mut conditions = vec![
+                    format!("type({path}) == \"table\""),
+                    if rest.is_some() {
+                        format!("#{path} >= {len}")
+                    } else {
+                        format!("#{path} == {len}")
+                    },
+                ];
+                let mut bindings = vec![];
+
+                for (index, item) in items.into_iter().enumerate() {
+                    let (condition, mut item_bindings) =
+                        self.pattern_to_condition(item, &format!("{path}[{}]", index + 1));
+
+                    conditions.extend(condition);
+                    bindings.append(&mut item_bindings);
+                }
+
+                if let Some(Some(rest)) = rest {
+                    bindings.push((rest, format!("{{ table.unpack({path}, {}) }}", len + 1)));
+                }
+
+                (Some(conditions.join(" and ")), bindings)
+            }
+            Pattern::Or(alternatives) => {
+                let mut conditions = vec![];
+
+                for alternative in alternatives {
+                    let (condition, bindings) = self.pattern_to_condition(alternative, path);
+
+                    if !bindings.is_empty() {
+                        self.diagnostics.push(Diagnostic::error(
+                            "patterns joined with '|' can't bind variables",
+                        ));
+                    }
+
+                    match condition {
+                        Some(condition) => conditions.push(condition),
+                        None => return (None, vec![]),
+                    }
+                }
+
+                (Some(format!("({})", conditions.join(" or "))), vec![])
+            }
+        }
+    }
+
+    /// Reports capitalized names in a match on an enum that aren't one of its
+    /// variants. They are most likely misspelled variants, which would
+    /// otherwise bind the value and match everything.
+    fn check_bindings(&mut self, arms: &[MatchArm]) {
+        let patterns = arms
+            .iter()
+            .flat_map(|arm| match &arm.pattern {
+                Pattern::Or(alternatives) => alternatives.iter().collect(),
+                pattern => vec![pattern],
+            })
+            .collect::<Vec<_>>();
+
+        let enum_name = patterns.iter().find_map(|pattern| match pattern {
+            Pattern::Variant {
+                enum_ident, ident, ..
+            } => self.find_variant(enum_ident.as_deref(), ident).ok(),
+            Pattern::Identifier(ident) => self.find_variant(None, ident).ok(),
+            _ => None,
+        });
+
+        let Some((enum_name, _)) = enum_name else {
+            return;
+        };
+
+        for pattern in patterns {
+            let Pattern::Identifier(ident) = pattern else {
+                continue;
+            };
+
+            if ident.starts_with(char::is_uppercase) && self.find_variant(None, ident).is_err() {
+                self.diagnostics.push(Diagnostic::error(format!(
+                    "'{enum_name}' has no variant '{ident}', use a lowercase name to bind the value"
+                )));
+            }
+        }
+    }
+
+    fn check_exhaustive(&mut self, arms: &[MatchArm]) {
+        let mut matched_enum = None;
+        let mut covered = vec![];
+
+        let patterns = arms
+            .iter()
+            .filter(|arm| arm.guard.is_none())
+            .flat_map(|arm| match &arm.pattern {
+                Pattern::Or(alternatives) => alternatives.iter().collect(),
+                pattern => vec![pattern],
+            });
+
+        for pattern in patterns {
+            let (enum_ident, ident, fields) = match pattern {
+                Pattern::Variant {
+                    enum_ident,
+                    ident,
+                    fields,
+                } => (enum_ident.as_deref(), ident, fields.as_slice()),
+                Pattern::Identifier(ident) if self.find_variant(None, ident).is_ok() => {
+                    (None, ident, [].as_slice())
+                }
+                _ => return,
+            };
+
+            let Ok((enum_name, _)) = self.find_variant(enum_ident, ident) else {
+                return;
+            };
+
+            if matched_enum.is_some_and(|matched_enum: &String| *matched_enum != enum_name) {
+                self.diagnostics.push(Diagnostic::error(format!(
+                    "match mixes variants of '{}' and '{enum_name}'",
+                    matched_enum.unwrap()
+                )));
+
+                return;
+            }
+
+            if fields.iter().all(|field| self.is_irrefutable(field)) {
+                covered.push(ident.clone());
+            }
+
+            matched_enum = Some(self.enums.get_key_value(&enum_name).unwrap().0);
+        }
+
+        if let Some(enum_name) = matched_enum {
+            let missing = self.enums[enum_name]
+                .iter()
+                .filter(|(variant, _)| !covered.contains(variant))
+                .map(|(variant, _)| format!("'{variant}'"))
+                .collect::<Vec<_>>();
+
+            if !missing.is_empty() {
+                self.diagnostics.push(Diagnostic::error(format!(
+                    "non-exhaustive match on '{enum_name}', missing {}",
+                    missing.join(", ")
+                )));
+            }
+        }
+    }
+
+    /// Emits a match as an `if`/`elseif` chain over a local holding the
+    /// scrutinee, so it is only evaluated once.
+    fn gen_match(&mut self, value: Expression, arms: Vec<MatchArm>) {
+        let value = self.expr_to_value(value);
+
+        self.write(GenType::VariableDeclaration {
+            local: true,
+            ident: "__match".into(),
+            value,
+            value_type: None,
+        });
+
+        self.check_bindings(&arms);
+        self.check_exhaustive(&arms);
+
+        let arm_count = arms.len();
+        let mut first = true;
+
+        for (index, arm) in arms.into_iter().enumerate() {
+            let (mut condition, bindings) = self.pattern_to_condition(arm.pattern, "__match");
+
+            if let Some(guard) = arm.guard {
+                let guard = self.expr_to_value(Self::substitute_bindings(guard, &bindings));
+
+                condition = Some(match condition {
+                    Some(condition) => format!("{condition} and ({guard})"),
+                    None => guard,
+                });
+            }
+
+            let irrefutable = condition.is_none();
+
+            match condition {
+                Some(condition) if first => {
+                    self.write(GenType::Raw(format!("if {condition} then")));
+                }
+                Some(condition) => {
+                    self.write(GenType::Raw(format!("elseif {condition} then")));
+                }
+                None if first => {}
+                None => self.write(GenType::Raw("else".into())),
+            }
+
+            let nested = !(irrefutable && first);
+
+            if nested {
+                self.nest += 1;
+            }
+
+            for (ident, path) in bindings {
+                self.write(GenType::VariableDeclaration {
+                    local: true,
+                    ident,
+                    value: path,
+                    value_type: None,
+                });
+            }
+
+            if let Statement::Scope(statements) = arm.body {
+                for stmt in statements {
+                    self.gen_statement(stmt);
+                }
+            } else {
+                self.gen_statement(arm.body);
+            }
+
+            if nested {
+                self.nest -= 1;
+            }
+
+            if irrefutable {
+                if index + 1 < arm_count {
+                    self.diagnostics.push(Diagnostic::warning(
+                        "unreachable match arm after catch-all pattern",
+                    ));
+                }
+
+                if !first {
+                    self.write(GenType::RScope);
+                }
+
+                return;
+            }
+
+            first = false;
+        }
+
+        if !first {
+            self.write(GenType::RScope);
+        }
+    }
+
+    #[allow(clippy::only_used_in_recursion)]
+    fn gen_statement(&mut self, stmt: Statement) {
+        // A `let` shadows constants with the same name
+        if let Statement::VariableDeclaration {
+            ident: Expression::Identifier(ident),
+            ..
+        } = &stmt
+        {
+            self.constants.remove(ident);
+        }
+
+        match stmt.clone() {
+            Statement::Luau(code) => {
+                self.write(GenType::Raw(code));
+            }
+            Statement::Import { idents, path } => {
+                if self.nest != 0 {
+                    self.diagnostics.push(Diagnostic::error(format!(
+                        "'{path}' can only be imported at the top level of a file"
+                    )));
+
+                    return;
+                }
+
+                let require = self
+                    .requires
+                    .get(&path)
+                    .cloned()
+                    .unwrap_or(format!("{path:?}"));
+
+                for ident in idents {
+                    self.write(GenType::VariableDeclaration {
+                        local: true,
+                        value: format!("require({require}).{ident}"),
+                        ident,
+                        value_type: None,
+                    });
+                }
+            }
+            Statement::Annotation(name) => {
+                if self.nest != 0 {
+                    self.diagnostics.push(Diagnostic::error(format!(
+                        "'@{name}' can only be used at the top of a file"
+                    )));
+                }
+            }
+            Statement::Export(stmt) => {
+                let ident = match stmt.as_ref() {
+                    Statement::VariableDeclaration { ident, .. }
+                    | Statement::Constant { ident, .. }
+                    | Statement::ClassConstructor { ident, .. }
+                    | Statement::Enum { ident, .. } => Some(self.ident_to_value(ident.clone())),
+                    // Interfaces are always exported as types
+                    _ => None,
+                };
+
+                if let (Some(ident), true) = (&ident, self.nest != 0) {
+                    self.diagnostics.push(Diagnostic::error(format!(
+                        "'{ident}' can only be exported from the top level of a file"
+                    )));
+                } else if let Some(ident) = ident {
+                    self.exports.push(ident);
+                }
+
+                self.gen_statement(*stmt);
+            }
+            Statement::Constant {
+                ident,
+                value_type,
+                value,
+            } => {
+                self.gen_statement(Statement::VariableDeclaration {
+                    ident: ident.clone(),
+                    value_type,
+                    value: value.clone(),
+                });
+
+                let literal = match &value {
+                    Expression::Negate(value) => matches!(value.as_ref(), Expression::Number(_)),
+                    value => matches!(
+                        value,
+                        Expression::String(_)
+                            | Expression::Char(_)
+                            | Expression::Bool(_)
+                            | Expression::Number(_)
+                    ),
+                };
+
+                if let (true, Expression::Identifier(ident)) = (literal, ident) {
+                    self.constants.insert(ident, value);
+                }
+            }
+            Statement::Return(expr) => {
+                let value = self.expr_to_value(expr);
+
+                self.write(GenType::Return { value });
+            }
+            Statement::TypeAlias {
+                ident,
+                generics,
+                value,
+                export,
+            } => {
+                if export && self.nest != 0 {
+                    self.diagnostics.push(Diagnostic::error(format!(
+                        "type '{ident}' can only be exported from the top level of a file"
+                    )));
+                }
+
+                let export = if export && self.nest == 0 {
+                    "export "
+                } else {
+                    ""
+                };
+                let generics = if generics.is_empty() {
+                    String::new()
+                } else {
+                    format!("<{}>", generics.join(", "))
+                };
+
+                self.write(GenType::Raw(format!(
+                    "{export}type {ident}{generics} = {value}"
+                )));
+            }
+            Statement::If { expr, body } => {
+                let expr = self.expr_to_value(expr);
+
+                self.write(GenType::LIf { expr });
+
+                self.nest += 1;
+
+                if let Statement::Scope(statements) = *body {
+                    for stmt in statements {
+                        self.gen_statement(stmt);
+                    }
+                } else {
+                    self.gen_statement(*body);
+                }
+
+                self.nest -= 1;
+
+                self.write(GenType::RScope);
+            }
+            Statement::Scope(statements) => {
+                self.write(GenType::LScope);
+                self.nest += 1;
+
+                for stmt in statements {
+                    self.gen_statement(stmt.clone());
+                }
+
+                self.nest -= 1;
+                self.write(GenType::RScope);
+            }
+            Statement::Interface {
+                ident,
+                properties,
+                methods,
+            } => {
+                self.declare(&stmt);
+                self.gen_interface(ident, properties, methods);
+            }
+            Statement::Enum { ident, variants } => {
+                self.declare(&stmt);
+                self.gen_enum(ident, variants);
+            }
+            Statement::Match { value, arms } => {
+                self.write(GenType::LScope);
+                self.nest += 1;
+                self.gen_match(value, arms);
+                self.nest -= 1;
+                self.write(GenType::RScope);
+            }
+            Statement::ClassConstructor {
+                ident,
+                generics,
+                body,
+                ..
+            } => {
+                let ident_str = self.expr_to_value(ident.clone());
+
+                if !generics.is_empty() {
+                    self.gen_class_type(&ident_str, &generics, &body);
+                }
+
+                let Expression::ClassBody {
+                    properties,
+                    methods,
+                    getters,
+                    setters,
+                    observers,
+                    metamethods,
+                } = body
+                else {
+                    panic!("{body:?} is not a valid class body")
+                };
+
+                self.write(GenType::VariableDeclaration {
+                    local: true,
+                    ident: ident_str.clone(),
+                    value: "{}".into(),
+                    value_type: None,
+                });
+
+                let accessors = !getters.is_empty() || !setters.is_empty() || !observers.is_empty();
+                let metatable = accessors || !metamethods.is_empty();
+
+                if metatable {
+                    self.write(GenType::VariableDeclaration {
+                        local: false,
+                        ident: format!("{ident_str}.__meta"),
+                        value: "{}".into(),
+                        value_type: None,
+                    });
+                }
+
+                self.gen_class_metamethods(&ident_str, &generics, metamethods);
+
+                if accessors {
+                    self.gen_class_accessors(
+                        &ident_str,
+                        &generics,
+                        getters,
+                        setters.clone(),
+                        observers.clone(),
+                    );
+                }
+
+                self.write(GenType::FunctionBody {
+                    local: false,
+                    ident: format!("{ident_str}.new"),
+                    signature: Signature {
+                        return_type: (!generics.is_empty())
+                            .then(|| format!("{ident_str}<{}>", generics.join(", "))),
+                        generics,
+                        ..Default::default()
+                    },
+                });
+
+                self.nest += 1;
+
+                self.write(GenType::VariableDeclaration {
+                    local: true,
+                    ident: String::from("self"),
+                    value: String::from("{}"),
+                    value_type: None,
+                });
+
+                if accessors {
+                    self.write(GenType::VariableDeclaration {
+                        local: false,
+                        ident: String::from("self.__props"),
+                        value: String::from("{}"),
+                        value_type: None,
+                    });
+                }
+
+                let observed = observers
+                    .iter()
+                    .chain(setters.iter())
+                    .map(|observer| match observer {
+                        Statement::VariableDeclaration { ident, .. } => {
+                            self.expr_to_value(ident.clone())
+                        }
+                        _ => unreachable!(),
+                    })
+                    .collect::<Vec<_>>();
+
+                for prop in properties {
+                    if let Statement::VariableDeclaration {
+                        ident,
+                        value_type,
+                        value,
+                    } = prop
+                    {
+                        let parens = needs_cast_parens(&value);
+                        let (inferred_type, mut value_str) = self.expr_to_value_with_type(value);
+
+                        if parens {
+                            value_str = format!("({value_str})");
+                        }
+
+                        let value_type_str = value_type
+                            .map(|value_type| value_type.to_string())
+                            .or(inferred_type);
+                        let ident = match ident {
+                            Expression::Identifier(ident) if observed.contains(&ident) => {
+                                format!("self.__props.{ident}")
+                            }
+                            Expression::Identifier(ident) => format!("self.{ident}"),
+                            ident => self.ident_to_value(ident),
+                        };
+
+                        self.write(GenType::VariableDeclaration {
+                            local: false,
+                            ident,
+                            value: value_str,
+                            value_type: value_type_str,
+                        })
+                    } else {
+                        todo!()
+                    }
+                }
+
+                for method in methods {
+                    if let Statement::VariableDeclaration { ident, value, .. } = method {
+                        if let Expression::Function {
+                            generics,
+                            params,
+                            return_type,
+                            stmt,
+                        } = value
+                        {
+                            let ident = self.ident_to_value(ident);
+                            let signature = self.gen_signature(generics, params, return_type);
+
+                            self.gen_function(
+                                GenType::MethodBody {
+                                    parent: "self".into(),
+                                    ident,
+                                    signature,
+                                },
+                                *stmt,
+                            );
+                        } else {
+                            panic!()
+                        }
+                    } else {
+                        todo!()
+                    }
+                }
+
+                if metatable {
+                    self.write(GenType::Raw(format!(
+                        "setmetatable(self, {ident_str}.__meta)"
+                    )));
+                }
+
+                self.write(GenType::Return {
+                    value: String::from("self"),
+                });
+
+                self.nest -= 1;
+
+                self.write(GenType::RScope);
+            }
+            Statement::VariableDeclaration {
+                ident,
+                value_type: Some(value_type),
+                value,
+            } => {
+                let ident = self.ident_to_value(ident);
+                let value = self.expr_to_value(value);
+
+                self.write(GenType::VariableDeclaration {
+                    local: true,
+                    ident,
+                    value,
+                    value_type: Some(value_type.to_string()),
+                });
+            }
+            Statement::VariableDeclaration { ident, value, .. }
+            | Statement::VariableAssignment { ident, value } => {
+                let local = matches!(stmt, Statement::VariableDeclaration { .. });
+
+                if let Expression::Identifier(_) = value {
+                    let ident = self.ident_to_value(ident);
+                    let value = self.expr_to_value(value);
+
+                    self.write(GenType::VariableDeclaration {
+                        local,
+                        ident,
+                        value,
+                        value_type: None,
+                    });
+                } else if let Expression::Function {
+                    generics,
+                    params,
+                    return_type,
+                    stmt,
+                } = value
+                {
+                    let ident = self.expr_to_value(ident);
+                    let signature = self.gen_signature(generics, params, return_type);
+
+                    self.gen_function(
+                        GenType::FunctionBody {
+                            local,
+                            ident,
+                            signature,
+                        },
+                        *stmt,
+                    );
+                } else {
+                    let parens = !local && needs_cast_parens(&value);
+                    let (type_str, mut value_str) = self.expr_to_value_with_type(value);
+
+                    if parens {
+                        value_str = format!("({value_str})");
+                    }
+
+                    let ident = self.ident_to_value(ident);
+
+                    self.write(GenType::VariableDeclaration {
+                        local,
+                        ident,
+                        value: value_str,
+                        value_type: type_str,
+                    });
+                }
+            }
+        }
+    }
+
+    pub fn run(&mut self) {
+        if let Statement::Scope(scope) = &self.root_stmt {
+            for stmt in scope.clone() {
+                self.declare(&stmt);
+            }
+        }
+
+        if let Statement::Scope(scope) = self.root_stmt.clone() {
+            for stmt in scope {
+                self.gen_statement(stmt.clone());
+            }
+        } else {
+            panic!("Root stmt must be a scope");
+        }
+
+        match self.kind {
+            Some(kind @ (ScriptKind::Server | ScriptKind::Client)) => {
+                for ident in &self.exports {
+                    self.diagnostics.push(Diagnostic::error(format!(
+                        "'{ident}' can't be exported from a {} script, only modules can be imported",
+                        kind.name()
+                    )));
+                }
+            }
+            // A module script has to return exactly one value, even without
+            // any exports
+            Some(ScriptKind::Module) => {
+                self.write(GenType::VariableDeclaration {
+                    local: true,
+                    ident: "__module".into(),
+                    value: "{}".into(),
+                    value_type: None,
+                });
+
+                for ident in self.exports.clone() {
+                    self.write(GenType::VariableDeclaration {
+                        local: false,
+                        ident: format!("__module.{ident}"),
+                        value: ident,
+                        value_type: None,
+                    });
+                }
+
+                self.write(GenType::Return {
+                    value: "__module".into(),
+                });
+            }
+            None if !self.exports.is_empty() => {
+                let exports = self
+                    .exports
+                    .iter()
+                    .map(|ident| format!("{ident} = {ident}"))
+                    .collect::<Vec<_>>();
+
+                self.write(GenType::Return {
+                    value: format!("{{ {} }}", exports.join(", ")),
+                });
+            }
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backend::{gen, gen_with_diagnostics};
+
+    fn errors(src: &str) -> Vec<String> {
+        gen_with_diagnostics(src)
+            .1
+            .into_iter()
+            .filter(|diagnostic| diagnostic.is_error())
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    #[test]
+    fn constants_are_not_inlined_into_member_names() {
+        let luau = gen("const speed = 10
+let car = workspace.Car
+print(car.speed)
+car.speed = 5
+car.body.speed = speed
+");
+
+        assert!(luau.contains("print(car.speed)"), "{luau}");
+        assert!(luau.contains("car.speed = 5"), "{luau}");
+        assert!(luau.contains("car.body.speed = 10"), "{luau}");
+    }
+
+    #[test]
+    fn constants_are_not_inlined_into_argument_labels() {
+        let luau = gen("const speed = 10
+let go = (speed: number, turn = 0) { return speed }
+go(turn = 1, speed = 2)
+");
+
+        assert!(luau.contains("go(2, 1)"), "{luau}");
+    }
+
+    #[test]
+    fn type_aliases_stay_below_a_leading_luau_block() {
+        let luau = gen(r"\\--!strict\\
+let a: Id = 1
+type Id = number
+");
+
+        assert!(luau.starts_with("--!strict\n"), "{luau}");
+    }
+
+    #[test]
+    fn setters_store_the_value_themselves() {
+        let luau = gen("class Car {
+    let speed = 0
+
+    set speed(value: number) {
+        if value > 100 {
+            self.speed = 100
+            return
+        }
+        self.speed = value
+    }
+}
+");
+
+        assert!(luau.contains("self.__props.speed = 100"), "{luau}");
+        assert!(luau.contains("self.__props.speed = value"), "{luau}");
+        assert!(!luau.contains("self.__props[key] = value"), "{luau}");
+    }
+
+    #[test]
+    fn accessors_go_through_metatables() {
+        let luau = gen("class Player {
+    let health = 100 didSet(old) {
+        print(old)
+    }
+
+    get alive() {
+        return self.health > 0
+    }
+}
+");
+
+        assert!(luau.contains("function Player.__getters:alive()"), "{luau}");
+        assert!(luau.contains("local old = self.__props.health"), "{luau}");
+        assert!(luau.contains("self.__props.health = 100"), "{luau}");
+        assert!(luau.contains("setmetatable(self, Player.__meta)"), "{luau}");
+    }
+
+    #[test]
+    fn plain_classes_have_no_metatable() {
+        let luau = gen("class Plain {
+    let x = 1
+}
+");
+
+        assert!(!luau.contains("__meta"), "{luau}");
+        assert!(luau.contains("self.x = 1"), "{luau}");
+    }
+
+    #[test]
+    fn fields_cant_have_a_setter_and_an_observer() {
+        let errors = errors(
+            "class A {
+    let x = 1 didSet(old) {
+        print(old)
+    }
+
+    set x(value) {
+        self.x = value
+    }
+}
+",
+        );
+
+        assert_eq!(
+            errors,
+            ["'A.x' can't have both a setter and a didSet observer"]
+        );
+    }
+
+    #[test]
+    fn operators_compile_to_metamethods() {
+        let luau = gen("class Vector {
+    let x = 0
+
+    operator +(other) {
+        return self
+    }
+
+    operator -() {
+        return self
+    }
+
+    tostring() {
+        return \"Vector\"
+    }
+}
+");
+
+        assert!(
+            luau.contains("function Vector.__meta:__add(other)"),
+            "{luau}"
+        );
+        assert!(luau.contains("function Vector.__meta:__unm()"), "{luau}");
+        assert!(
+            luau.contains("function Vector.__meta:__tostring()"),
+            "{luau}"
+        );
+        assert!(luau.contains("setmetatable(self, Vector.__meta)"), "{luau}");
+    }
+
+    #[test]
+    fn interfaces_are_exported_types() {
+        let luau = gen("interface Damageable {
+    health: number
+}
+");
+
+        assert!(
+            luau.contains("export type Damageable = {\n    health: number,\n}"),
+            "{luau}"
+        );
+    }
+
+    #[test]
+    fn enums_compile_to_frozen_tagged_tables() {
+        let luau = gen("enum State { Idle, Attacking(target: Model) }
+");
+
+        assert!(
+            luau.contains(
+                "export type State = { tag: \"Idle\" } | { tag: \"Attacking\", target: Model }"
+            ),
+            "{luau}"
+        );
+        assert!(
+            luau.contains("Idle = table.freeze({ tag = \"Idle\" })"),
+            "{luau}"
+        );
+    }
+
+    #[test]
+    fn matches_must_cover_every_variant() {
+        let errors = errors(
+            "enum State { Idle, Walking, Attacking(target: Model) }
+let state = State.Idle
+match state {
+    Idle => print(1),
+}
+",
+        );
+
+        assert_eq!(
+            errors,
+            ["non-exhaustive match on 'State', missing 'Walking', 'Attacking'"]
+        );
+    }
+
+    #[test]
+    fn wildcards_and_bindings_make_matches_exhaustive() {
+        let src = "enum State { Idle, Walking, Attacking(target: Model) }
+let state = State.Idle
+match state {
+    Attacking(target) => print(target),
+    _ => print(1),
+}
+match state {
+    Idle => print(1),
+    other => print(other),
+}
+";
+
+        assert!(errors(src).is_empty(), "{:#?}", errors(src));
+        assert!(gen(src).contains("local target = __match.target"));
+    }
+
+    #[test]
+    fn unknown_variants_are_errors() {
+        let errors = errors(
+            "enum State { Idle, Walking }
+let state = State.Idle
+match state {
+    Idle => print(1),
+    Runing => print(2),
+    _ => print(3),
+}
+",
+        );
+
+        assert_eq!(
+            errors,
+            ["'State' has no variant 'Runing', use a lowercase name to bind the value"]
+        );
+    }
+
+    #[test]
+    fn patterns_compile_to_an_if_chain() {
+        let luau = gen("let describe = (value) {
+    return match value {
+        1 | 2 => \"small\",
+        3..=10 => \"medium\",
+        11..20 => \"big\",
+        { x, y: 0 } => x,
+        [first, ..rest] if first > 1 => rest,
+        _ => \"other\",
+    }
+}
+");
+
+        for condition in [
+            "if (__match == 1 or __match == 2) then",
+            "elseif type(__match) == \"number\" and __match >= 3 and __match <= 10 then",
+            "elseif type(__match) == \"number\" and __match >= 11 and __match < 20 then",
+            "elseif type(__match) == \"table\" and __match.x ~= nil and __match.y == 0 then",
+            "elseif type(__match) == \"table\" and #__match >= 1 and (__match[1] > 1) then",
+            "local rest = { table.unpack(__match, 2) }",
+        ] {
+            assert!(luau.contains(condition), "{condition}\n{luau}");
+        }
+    }
+
+    #[test]
+    fn matched_values_are_evaluated_once() {
+        let luau = gen("match get() {
+    1 => print(1),
+    2 => print(2),
+}
+");
+
+        assert_eq!(luau.matches("get()").count(), 1, "{luau}");
+        assert!(luau.contains("local __match = get()"), "{luau}");
+    }
+
+    #[test]
+    fn methods_and_anonymous_functions_keep_their_parameters() {
+        let luau = gen("class Car {
+    let drive = (speed: number, turn) {
+        return speed
+    }
+}
+let run = (callback) { callback(1, 2) }
+run((x, y) { print(x, y) })
+");
+
+        assert!(
+            luau.contains("function self:drive(speed: number, turn)"),
+            "{luau}"
+        );
+        assert!(luau.contains("run(function(x, y)"), "{luau}");
+    }
+
+    #[test]
+    fn defaults_varargs_and_named_arguments() {
+        let luau = gen(
+            "let spawn = (pos: Vector3, team: string = \"red\", speed = 10, ...tags: string) {
+    print(pos, team, speed, tags)
+}
+spawn(pos = workspace.Spawn.Position, speed = 5)
+",
+        );
+
+        assert!(
+            luau.contains(
+                "local function spawn(pos: Vector3, team: string?, speed: number?, ...: string)"
+            ),
+            "{luau}"
+        );
+        assert!(
+            luau.contains("if team == nil then team = \"red\" end"),
+            "{luau}"
+        );
+        assert!(luau.contains("local tags = { ... }"), "{luau}");
+        assert!(
+            luau.contains("spawn(workspace.Spawn.Position, nil, 5)"),
+            "{luau}"
+        );
+    }
+
+    #[test]
+    fn named_arguments_must_match_parameters() {
+        let errors = errors(
+            "let f = (a, b = 1) { return a }
+f(c = 1, a = 1)
+f(1, a = 2)
+",
+        );
+
+        assert_eq!(
+            errors,
+            [
+                "'f' has no parameter named 'c'",
+                "parameter 'a' of 'f' is passed more than once",
+            ]
+        );
+    }
+
+    #[test]
+    fn type_annotations_are_emitted_as_written() {
+        let luau = gen(
+            "let apply = (list: {number}, f: (number) -> string?): { [number]: string } {
+    return list
+}
+let find = (name: string | Instance, ...rest: any): (boolean, Part?) {
+    return true
+}
+",
+        );
+
+        assert!(
+            luau.contains(
+                "local function apply(list: {number}, f: (number) -> string?): { [number]: string }"
+            ),
+            "{luau}"
+        );
+        assert!(
+            luau.contains(
+                "local function find(name: string | Instance, ...: any): (boolean, Part?)"
+            ),
+            "{luau}"
+        );
+    }
+
+    #[test]
+    fn let_declarations_keep_their_annotations() {
+        let luau = gen("let target: Model? = nil
+let scores: { [string]: number } = workspace.Scores
+class Car {
+    let speed: number = 0
+    let name = \"x\"
+}
+");
+
+        assert!(luau.contains("local target: Model? = nil"), "{luau}");
+        assert!(
+            luau.contains("local scores: { [string]: number } = workspace.Scores"),
+            "{luau}"
+        );
+        assert!(luau.contains("self.speed = 0 :: number"), "{luau}");
+        assert!(luau.contains("self.name = \"x\" :: string"), "{luau}");
+    }
+
+    #[test]
+    fn type_aliases_and_exported_types() {
+        let luau = gen("type Inventory = { [string]: number }
+export type Pair<A, B> = { first: A, second: B }
+");
+
+        assert!(
+            luau.contains("type Inventory = { [string]: number }"),
+            "{luau}"
+        );
+        assert!(
+            luau.contains("export type Pair<A, B> = { first: A, second: B }"),
+            "{luau}"
+        );
+    }
+
+    #[test]
+    fn types_are_only_exported_from_the_top_level() {
+        let errors = errors(
+            "let f = () {
+    export type Id = number
+}
+",
+        );
+
+        assert_eq!(
+            errors,
+            ["type 'Id' can only be exported from the top level of a file"]
+        );
+    }
+
+    #[test]
+    fn generic_functions_and_classes() {
+        let luau = gen("let map = <T, U>(list: {T}, f: (T) -> U): {U} {
+    return list
+}
+class Stack<T> {
+    let items: {T} = workspace.Items
+
+    let pop = (): T? {
+        return nil
+    }
+}
+");
+
+        assert!(
+            luau.contains("local function map<T, U>(list: {T}, f: (T) -> U): {U}"),
+            "{luau}"
+        );
+        assert!(
+            luau.contains(
+                "export type Stack<T> = {\n    items: {T},\n    pop: (self: Stack<T>) -> T?,\n}"
+            ),
+            "{luau}"
+        );
+        assert!(luau.contains("function Stack.new<T>(): Stack<T>"), "{luau}");
+    }
+
+    #[test]
+    fn inferred_types_are_only_written_in_strict_files() {
+        let src = "let greet = (name: string) {
+    return name
+}
+let message = greet(\"Bob\")
+";
+
+        let luau = gen(src);
+        assert!(luau.contains("local message = greet(\"Bob\")"), "{luau}");
+
+        let luau = gen(&format!(
+            r"\\--!strict\\
+{src}"
+        ));
+        assert!(
+            luau.contains("local message: string = greet(\"Bob\")"),
+            "{luau}"
+        );
+    }
+
+    #[test]
+    fn only_literal_constants_are_inlined() {
+        let luau = gen("const MAX = 50
+const PART = workspace.Part
+let speed = MAX
+print(PART)
+");
+
+        assert!(luau.contains("local speed = 50"), "{luau}");
+        assert!(luau.contains("print(PART)"), "{luau}");
     }
 }
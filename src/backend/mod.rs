@@ -1,25 +1,342 @@
+use std::{collections::HashMap, path::Path};
+
+use serde::Deserialize;
+
 use crate::{
     backend::codegen::CodeGen,
-    frontend::{lexer::Lexer, parser::Parser},
+    frontend::{
+        checker::Checker,
+        lexer::{Lexer, Token},
+        modules,
+        parser::{self, Statement, SyntaxError},
+        resolver::{Resolver, LUAU_GLOBALS},
+        scripts::{self, ScriptKind, CLIENT_APIS, SERVER_APIS},
+    },
+    util::diagnostic::{Diagnostic, LintLevel},
 };
 
 pub mod codegen;
 
+/// Maps a file to the Luau expression that requires it.
+pub type ResolveFile = Box<dyn Fn(&Path) -> Option<String> + Send + Sync>;
+
+/// How the path of an `import` is turned into the argument of `require`.
+#[derive(Default)]
+pub enum RequireStrategy {
+    /// `require("./vehicles")`
+    #[default]
+    Relative,
+    /// `require(script.Parent.vehicles)`
+    Instance,
+    /// Maps the import path to any Luau expression
+    Custom(Box<dyn Fn(&str) -> String + Send + Sync>),
+    /// Maps the file an import resolves to to a Luau expression, imports it
+    /// doesn't know about are required like `Instance` does
+    File(ResolveFile),
+}
+
+const LUAU_KEYWORDS: [&str; 22] = [
+    "and", "break", "continue", "do", "else", "elseif", "end", "export", "false", "for",
+    "function", "if", "in", "local", "nil", "not", "or", "repeat", "return", "then", "true",
+    "until",
+];
+
+/// Appends the child `name` to an instance path.
+pub fn index_instance(instance: &mut String, name: &str) {
+    if !name.is_empty()
+        && !LUAU_KEYWORDS.contains(&name)
+        && !name.starts_with(|char: char| char.is_ascii_digit())
+        && name
+            .chars()
+            .all(|char| char.is_alphanumeric() || char == '_')
+    {
+        *instance += &format!(".{name}");
+    } else {
+        *instance += &format!("[{name:?}]");
+    }
+}
+
+impl RequireStrategy {
+    /// The argument of `require` for an import written in a file in `dir`.
+    pub fn require_path(&self, dir: Option<&Path>, path: &str) -> String {
+        if let RequireStrategy::File(resolve) = self {
+            return dir
+                .and_then(|dir| resolve(&modules::resolve_import(dir, path)))
+                .unwrap_or_else(|| RequireStrategy::Instance.require_path(dir, path));
+        }
+
+        let path = path.strip_suffix(".cop").unwrap_or(path);
+
+        match self {
+            RequireStrategy::Relative => format!("{path:?}"),
+            RequireStrategy::Instance => {
+                let mut instance = String::from("script.Parent");
+
+                for part in path.split('/') {
+                    match part {
+                        "" | "." => {}
+                        ".." => instance += ".Parent",
+                        part => index_instance(&mut instance, part),
+                    }
+                }
+
+                instance
+            }
+            RequireStrategy::Custom(resolve) => resolve(path),
+            RequireStrategy::File(_) => unreachable!(),
+        }
+    }
+}
+
+/// The Luau environment the generated code runs in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Target {
+    #[default]
+    Roblox,
+    /// Plain Luau, without the Roblox globals
+    Luau,
+}
+
+pub struct Options {
+    pub require: RequireStrategy,
+    pub target: Target,
+    /// Emits `--!strict` and the inferred types of `let` bindings
+    pub strict: bool,
+    /// Globals that can be used besides the ones of the target
+    pub globals: Vec<String>,
+    pub lints: HashMap<String, LintLevel>,
+    /// APIs that server scripts are warned about
+    pub client_apis: Vec<String>,
+    /// APIs that client scripts are warned about
+    pub server_apis: Vec<String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            require: RequireStrategy::default(),
+            target: Target::default(),
+            strict: false,
+            globals: vec![],
+            lints: HashMap::new(),
+            client_apis: CLIENT_APIS.iter().map(|api| api.to_string()).collect(),
+            server_apis: SERVER_APIS.iter().map(|api| api.to_string()).collect(),
+        }
+    }
+}
+
 pub fn gen(scr: &str) -> String {
+    gen_with_diagnostics(scr).0
+}
+
+pub fn gen_with_diagnostics(scr: &str) -> (String, Vec<Diagnostic>) {
+    gen_file(scr, None, &Options::default())
+}
+
+pub fn tokens(scr: &str) -> Result<Vec<Token>, String> {
+    Lexer::new(scr).load().cloned()
+}
+
+/// Parses and checks a file, filling in the types the checker infers. Its
+/// imports are checked against the files next to it when its path is known.
+pub fn analyze(scr: &str, path: Option<&Path>, options: &Options) -> (Statement, Vec<Diagnostic>) {
+    check(parser::parse(scr), scr, path, options)
+}
+
+/// Checks a parsed file. A syntax error is its only diagnostic, along with
+/// the statements before it.
+fn check(
+    parsed: Result<Statement, SyntaxError>,
+    scr: &str,
+    path: Option<&Path>,
+    options: &Options,
+) -> (Statement, Vec<Diagnostic>) {
+    let mut expression = match parsed {
+        Ok(expression) => expression,
+        Err(error) => return (*error.parsed, vec![Diagnostic::error(error.message)]),
+    };
+
+    let mut diagnostics = match path.and_then(Path::parent) {
+        Some(dir) => modules::check_imports(&expression, dir),
+        None => vec![],
+    };
+
+    let (kind, mut kind_diagnostics) = scripts::script_kind(&expression, path);
+    diagnostics.append(&mut kind_diagnostics);
+
     let mut lexer = Lexer::new(scr);
-    let tokens = lexer.load();
+    // A file that parsed always lexes
+    let tokens = lexer.load().cloned().unwrap_or_default();
+
+    diagnostics.append(&mut lexer.diagnostics);
+
+    if let Some(kind) = kind {
+        diagnostics.extend(scripts::check_apis(
+            &tokens,
+            kind,
+            &options.client_apis,
+            &options.server_apis,
+        ));
+    }
+
+    let mut resolver = Resolver::default();
+
+    if options.target == Target::Luau {
+        resolver.globals = LUAU_GLOBALS
+            .iter()
+            .map(|global| global.to_string())
+            .collect();
+    }
+
+    resolver.globals.extend(options.globals.iter().cloned());
+    resolver.run(&expression);
+
+    let mut checker = Checker::default();
+    checker.strict = options.strict;
+    checker.run(&mut expression);
 
-    #[cfg(debug_assertions)]
-    println!("{tokens:#?}");
+    diagnostics.append(&mut resolver.reporter.diagnostics);
+    diagnostics.append(&mut checker.reporter.diagnostics);
 
-    let mut parser = Parser::new(tokens);
-    let expression = parser.load();
+    let diagnostics = diagnostics
+        .into_iter()
+        .filter_map(
+            |diagnostic| match diagnostic.lint.and_then(|lint| options.lints.get(lint)) {
+                Some(level) => diagnostic.with_level(*level),
+                None => Some(diagnostic),
+            },
+        )
+        .collect();
+
+    (expression, diagnostics)
+}
+
+/// A compiled file, with what it needs from and gives to other files.
+pub struct Compiled {
+    pub luau: String,
+    pub diagnostics: Vec<Diagnostic>,
+    /// The import paths, as written in the file
+    pub imports: Vec<String>,
+    /// `None` when the file couldn't be parsed
+    pub exports: Option<Vec<String>>,
+    pub kind: Option<ScriptKind>,
+}
+
+/// Compiles a file to Luau.
+pub fn gen_file(scr: &str, path: Option<&Path>, options: &Options) -> (String, Vec<Diagnostic>) {
+    let compiled = compile(scr, path, options);
+
+    (compiled.luau, compiled.diagnostics)
+}
 
-    #[cfg(debug_assertions)]
-    println!("{expression:#?}");
+/// Compiles a file to Luau, also returning its imports and exports.
+pub fn compile(scr: &str, path: Option<&Path>, options: &Options) -> Compiled {
+    let parsed = parser::parse(scr);
+    let exports = parsed.as_ref().ok().map(modules::exports);
+    let (expression, mut diagnostics) = check(parsed, scr, path, options);
+    let dir = path.and_then(Path::parent);
+
+    let imports = modules::imports(&expression);
+    let (kind, _) = scripts::script_kind(&expression, path);
+
+    if exports.is_none() {
+        return Compiled {
+            luau: String::new(),
+            diagnostics,
+            imports,
+            exports,
+            kind,
+        };
+    }
+    let requires = imports
+        .iter()
+        .map(|import| (import.clone(), options.require.require_path(dir, import)))
+        .collect();
 
     let mut codegen = CodeGen::new(expression);
+    codegen.requires = requires;
+    codegen.kind = kind;
     codegen.run();
 
-    codegen.src
+    diagnostics.append(&mut codegen.diagnostics);
+
+    let luau = if options.strict && !codegen.src.trim_start().starts_with("--!strict") {
+        format!("--!strict\n{}", codegen.src)
+    } else {
+        codegen.src
+    };
+
+    Compiled {
+        luau,
+        diagnostics,
+        imports,
+        exports,
+        kind,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn imports_are_required_by_path_or_instance() {
+        let dir = Some(Path::new("src"));
+
+        assert_eq!(
+            RequireStrategy::Relative.require_path(dir, "./vehicles.cop"),
+            "\"./vehicles\""
+        );
+        assert_eq!(
+            RequireStrategy::Instance.require_path(dir, "../shared/my-vehicles"),
+            "script.Parent.Parent.shared[\"my-vehicles\"]"
+        );
+        assert_eq!(
+            RequireStrategy::Custom(Box::new(|name| format!("game.{name}")))
+                .require_path(dir, "vehicles"),
+            "game.vehicles"
+        );
+    }
+
+    #[test]
+    fn files_with_exports_return_them() {
+        let compiled = compile(
+            "export const WHEELS = 4\nexport let speed = 1\n",
+            None,
+            &Options::default(),
+        );
+
+        assert!(
+            compiled
+                .luau
+                .ends_with("return { WHEELS = WHEELS, speed = speed }\n"),
+            "{}",
+            compiled.luau
+        );
+        assert_eq!(
+            compiled.exports,
+            Some(vec!["WHEELS".into(), "speed".into()])
+        );
+    }
+
+    #[test]
+    fn modules_return_a_table_and_scripts_cant_export() {
+        let compiled = compile("@module\nlet speed = 1\n", None, &Options::default());
+
+        assert!(
+            compiled
+                .luau
+                .ends_with("local __module = {}\nreturn __module\n"),
+            "{}",
+            compiled.luau
+        );
+
+        let compiled = compile("@server\nexport let speed = 1\n", None, &Options::default());
+
+        assert_eq!(
+            compiled.diagnostics[0].message,
+            "'speed' can't be exported from a server script, only modules can be imported"
+        );
+    }
 }
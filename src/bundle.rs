@@ -0,0 +1,313 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{
+    backend::{self, Compiled, Options, RequireStrategy},
+    frontend::{modules, scripts::ScriptKind},
+    util::diagnostic::Diagnostic,
+};
+
+/// Defines the module registry and a `require` that runs each bundled module
+/// the first time it's required, like separate ModuleScripts would be. Other
+/// arguments are passed on to the real `require`.
+const SHIM: &str = "\
+local __modules = {}
+local __loaded = {}
+local __results = {}
+local __require = require
+
+local function require(module)
+    local load = __modules[module]
+
+    if load == nil then
+        return __require(module)
+    end
+
+    if not __loaded[module] then
+        __loaded[module] = true
+        __results[module] = load()
+    end
+
+    return __results[module]
+end
+";
+
+/// An entry file and everything it imports, compiled into one Luau file.
+pub struct Bundle {
+    pub luau: String,
+    /// The kind of script the entry file is
+    pub kind: Option<ScriptKind>,
+    pub diagnostics: Vec<(PathBuf, Diagnostic)>,
+}
+
+impl Bundle {
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|(_, diagnostic)| diagnostic.is_error())
+    }
+}
+
+/// The name a file is registered under, its path relative to the directory
+/// of the entry file without the extension.
+fn module_name(root: &Path, path: &Path) -> String {
+    let root = root.components().collect::<Vec<_>>();
+    let path = path.with_extension("");
+    let path = path.components().collect::<Vec<_>>();
+
+    let common = root.iter().zip(&path).take_while(|(a, b)| a == b).count();
+
+    let parents = root[common..].iter().map(|_| Component::ParentDir);
+
+    parents
+        .chain(path[common..].iter().copied())
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Compiles a file after the files it imports, skipping files that were
+/// already compiled. Files that can't be read were already reported by the
+/// file importing them.
+fn visit(
+    path: &Path,
+    options: &Options,
+    visited: &mut HashSet<PathBuf>,
+    modules: &mut Vec<(PathBuf, Compiled)>,
+    diagnostics: &mut Vec<(PathBuf, Diagnostic)>,
+) {
+    if !visited.insert(path.to_path_buf()) {
+        return;
+    }
+
+    let Ok(src) = fs::read_to_string(path) else {
+        return;
+    };
+
+    let compiled = backend::compile(&src, Some(path), options);
+    let dir = path.parent().unwrap();
+
+    for import in &compiled.imports {
+        let import = modules::resolve_import(dir, import);
+
+        visit(&import, options, visited, modules, diagnostics);
+    }
+
+    diagnostics.extend(
+        compiled
+            .diagnostics
+            .iter()
+            .map(|diagnostic| (path.to_path_buf(), diagnostic.clone())),
+    );
+    modules.push((path.to_path_buf(), compiled));
+}
+
+/// Compiles `entry` and every file it imports into one Luau file. Imported
+/// files become functions in a module registry, and the entry file runs
+/// last at the top level. Fails when the entry isn't a `.cop` file that can
+/// be read.
+pub fn bundle(entry: &Path, mut options: Options) -> Result<Bundle, String> {
+    if entry.extension().is_none_or(|extension| extension != "cop") {
+        return Err(format!(
+            "can't bundle '{}', the entry must be a .cop file",
+            entry.display()
+        ));
+    }
+
+    let root = entry.parent().unwrap().to_path_buf();
+    let strict = options.strict;
+
+    // Resolved like an import, so imports of the entry file can be found
+    let entry = modules::resolve_import(&root, &entry.file_name().unwrap().to_string_lossy());
+
+    let registry = root.clone();
+    options.require = RequireStrategy::File(Box::new(move |path| {
+        Some(format!("{:?}", module_name(&registry, path)))
+    }));
+
+    let mut modules = vec![];
+    let mut diagnostics = vec![];
+
+    visit(
+        &entry,
+        &options,
+        &mut HashSet::new(),
+        &mut modules,
+        &mut diagnostics,
+    );
+
+    // The entry is visited first, so it's compiled last
+    let Some((entry_path, entry)) = modules.pop().filter(|(path, _)| *path == entry) else {
+        return Err(format!("can't read '{}'", entry.display()));
+    };
+
+    // The entry file isn't in the registry, it runs at the top level
+    for (path, module) in &modules {
+        let dir = path.parent().unwrap();
+
+        if module
+            .imports
+            .iter()
+            .any(|import| modules::resolve_import(dir, import) == entry_path)
+        {
+            diagnostics.push((
+                path.clone(),
+                Diagnostic::error("the entry file of a bundle can't be imported"),
+            ));
+        }
+    }
+
+    let mut luau = String::new();
+
+    if strict {
+        luau += "--!strict\n";
+    }
+
+    luau += SHIM;
+
+    for (path, module) in modules {
+        luau += &format!(
+            "\n__modules[{:?}] = function()\n",
+            module_name(&root, &path)
+        );
+
+        for line in module.luau.lines() {
+            // The whole bundle is strict already, and types can only be
+            // exported from the top level
+            let line = match line {
+                "--!strict" => continue,
+                line => line.strip_prefix("export ").unwrap_or(line),
+            };
+
+            if line.is_empty() {
+                luau.push('\n');
+            } else {
+                luau += &format!("    {line}\n");
+            }
+        }
+
+        luau += "end\n";
+    }
+
+    luau.push('\n');
+    luau += entry
+        .luau
+        .strip_prefix("--!strict\n")
+        .unwrap_or(&entry.luau);
+
+    diagnostics.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    Ok(Bundle {
+        luau,
+        kind: entry.kind,
+        diagnostics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    fn dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("copiler-bundle-{name}-{}", process::id()));
+
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        dir
+    }
+
+    #[test]
+    fn entries_must_be_cop_files() {
+        let dir = dir("entry");
+
+        fs::write(dir.join("notes.txt"), "print(1)").unwrap();
+
+        assert!(bundle(&dir.join("notes.txt"), Options::default()).is_err());
+        assert!(bundle(&dir.join("missing.cop"), Options::default()).is_err());
+    }
+
+    #[test]
+    fn modules_are_named_relative_to_the_entry() {
+        let root = Path::new("game/src");
+
+        assert_eq!(
+            module_name(root, Path::new("game/src/shared/util.cop")),
+            "shared/util"
+        );
+        assert_eq!(
+            module_name(root, Path::new("game/lib/math.cop")),
+            "../lib/math"
+        );
+    }
+
+    #[test]
+    fn imports_are_bundled_once_before_the_entry() {
+        let dir = dir("imports");
+
+        fs::create_dir_all(dir.join("shared")).unwrap();
+        fs::write(dir.join("shared/math.cop"), "export const TWO = 2\n").unwrap();
+        fs::write(
+            dir.join("shared/util.cop"),
+            "import { TWO } from \"./math\"\nexport let double = (x) { return x * TWO }\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("main.cop"),
+            "import { TWO } from \"./shared/math\"\nimport { double } from \"./shared/util\"\nprint(double(TWO))\n",
+        )
+        .unwrap();
+
+        let bundle = bundle(&dir.join("main.cop"), Options::default()).unwrap();
+        let luau = &bundle.luau;
+
+        assert!(!bundle.has_errors());
+        assert_eq!(
+            luau.matches("__modules[\"shared/math\"] = function()")
+                .count(),
+            1
+        );
+
+        let math = luau.find("__modules[\"shared/math\"]").unwrap();
+        let util = luau.find("__modules[\"shared/util\"]").unwrap();
+        let main = luau.find("require(\"shared/util\").double").unwrap();
+
+        assert!(math < util && util < main, "{luau}");
+    }
+
+    #[test]
+    fn entries_cant_be_imported() {
+        let dir = dir("cycle");
+
+        fs::write(
+            dir.join("util.cop"),
+            "import { x } from \"./main\"\nexport let y = x\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("main.cop"),
+            "import { y } from \"./util\"\nexport let x = 1\n",
+        )
+        .unwrap();
+
+        let bundle = bundle(&dir.join("main.cop"), Options::default()).unwrap();
+        let errors = bundle
+            .diagnostics
+            .iter()
+            .map(|(path, diagnostic)| (path.file_name().unwrap(), diagnostic.message.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            errors,
+            [(
+                "util.cop".as_ref(),
+                "the entry file of a bundle can't be imported"
+            )]
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    frontend::{modules::Signature, resolver::LINTS, scripts::ScriptKind},
+    util::diagnostic::{Diagnostic, Severity},
+};
+
+pub const FILE_NAME: &str = ".copiler-cache.json";
+
+pub fn hash(value: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CachedDiagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub lint: Option<String>,
+}
+
+impl From<&Diagnostic> for CachedDiagnostic {
+    fn from(diagnostic: &Diagnostic) -> Self {
+        Self {
+            severity: diagnostic.severity,
+            message: diagnostic.message.clone(),
+            lint: diagnostic.lint.map(String::from),
+        }
+    }
+}
+
+impl From<&CachedDiagnostic> for Diagnostic {
+    fn from(diagnostic: &CachedDiagnostic) -> Self {
+        Self {
+            severity: diagnostic.severity,
+            message: diagnostic.message.clone(),
+            lint: diagnostic
+                .lint
+                .as_ref()
+                .and_then(|lint| LINTS.iter().find(|name| *name == lint).copied()),
+        }
+    }
+}
+
+/// The result of compiling one file.
+#[derive(Serialize, Deserialize)]
+pub struct Entry {
+    /// The hash of the source it was compiled from
+    pub hash: u64,
+    /// `None` when the file had errors
+    pub luau: Option<String>,
+    /// `None` when the file couldn't be parsed
+    pub signature: Option<Signature>,
+    pub kind: Option<ScriptKind>,
+    /// The signature of every imported file at the time, `None` for files
+    /// that didn't exist or couldn't be parsed. The file is checked again when one of them changes.
+    pub dependencies: BTreeMap<PathBuf, Option<Signature>>,
+    pub diagnostics: Vec<CachedDiagnostic>,
+}
+
+/// What the last build compiled, stored next to its outputs.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Cache {
+    /// Changes with the compiler version and the project settings, either
+    /// of which makes every entry stale
+    pub fingerprint: u64,
+    pub files: BTreeMap<PathBuf, Entry>,
+    /// Every file builds wrote to the output directory, the only files a
+    /// build removes again
+    #[serde(default)]
+    pub outputs: BTreeSet<PathBuf>,
+}
+
+impl Cache {
+    /// Reads the cache from the output directory, an empty one when it's
+    /// missing or unreadable. Only the outputs are kept when it was made
+    /// with other settings, so they can still be cleaned up.
+    pub fn load(out: &Path, fingerprint: u64) -> Self {
+        let cache = fs::read_to_string(out.join(FILE_NAME))
+            .ok()
+            .and_then(|src| serde_json::from_str::<Cache>(&src).ok());
+
+        match cache {
+            Some(cache) if cache.fingerprint == fingerprint => cache,
+            cache => Self {
+                fingerprint,
+                files: BTreeMap::new(),
+                outputs: cache.map(|cache| cache.outputs).unwrap_or_default(),
+            },
+        }
+    }
+
+    pub fn save(&self, out: &Path) {
+        fs::create_dir_all(out).expect("Failed to create output directory");
+        fs::write(
+            out.join(FILE_NAME),
+            serde_json::to_string(self).expect("Failed to serialize cache"),
+        )
+        .expect("Failed to write cache");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    fn entry() -> Entry {
+        Entry {
+            hash: hash("print(1)"),
+            luau: Some("print(1)".into()),
+            signature: None,
+            kind: None,
+            dependencies: BTreeMap::new(),
+            diagnostics: vec![(&Diagnostic::warning("'x' shadows an earlier declaration")
+                .with_lint("shadowing"))
+                .into()],
+        }
+    }
+
+    #[test]
+    fn other_settings_only_keep_the_outputs() {
+        let out = env::temp_dir().join(format!("copiler-cache-{}", process::id()));
+        let mut cache = Cache {
+            fingerprint: 1,
+            ..Default::default()
+        };
+
+        cache.files.insert("src/a.cop".into(), entry());
+        cache.outputs.insert("dist/a.luau".into());
+        cache.save(&out);
+
+        let loaded = Cache::load(&out, 1);
+
+        assert!(loaded.files.contains_key(Path::new("src/a.cop")));
+
+        let loaded = Cache::load(&out, 2);
+
+        assert_eq!(loaded.fingerprint, 2);
+        assert!(loaded.files.is_empty());
+        assert!(loaded.outputs.contains(Path::new("dist/a.luau")));
+    }
+
+    #[test]
+    fn diagnostics_keep_their_lint() {
+        let diagnostic = Diagnostic::from(&entry().diagnostics[0]);
+
+        assert_eq!(diagnostic.lint, Some("shadowing"));
+        assert!(!diagnostic.is_error());
+    }
+}
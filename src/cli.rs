@@ -0,0 +1,181 @@
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: copiler <command> [options] [file]
+
+Commands:
+    build        Compile a file, or the whole project without one
+    check        Report diagnostics without writing any output
+    fmt          Format a file in place, or every file of the project
+    watch        Rebuild changed files, and the files importing them
+    emit-ast     Print the syntax tree of a file, with inferred types
+    emit-tokens  Print the tokens of a file
+
+Options:
+    -o, --out <path>  Write to this file, or this directory for a project build or watch
+        --stdout      Write the output to stdout instead of a file
+        --bundle      Build a file and everything it imports into one file
+    -q, --quiet       Only print errors
+    -h, --help        Print this help
+    -V, --version     Print the version
+
+Passing `-` as the file reads it from stdin and writes the output to stdout.
+";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Command {
+    Build,
+    Check,
+    Fmt,
+    Watch,
+    EmitAst,
+    EmitTokens,
+    Help,
+    Version,
+}
+
+#[derive(Debug)]
+pub struct Cli {
+    pub command: Command,
+    /// The file to work on, `-` for stdin, or `None` for the whole project
+    pub input: Option<String>,
+    pub out: Option<PathBuf>,
+    pub stdout: bool,
+    /// Compiles the input and its imports into a single file
+    pub bundle: bool,
+    pub quiet: bool,
+}
+
+impl Cli {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut cli = Cli {
+            command: Command::Help,
+            input: None,
+            out: None,
+            stdout: false,
+            bundle: false,
+            quiet: false,
+        };
+        let mut command = None;
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-o" | "--out" => {
+                    let out = args.next().ok_or(format!("'{arg}' expects a path"))?;
+
+                    cli.out = Some(out.into());
+                }
+                "--stdout" => cli.stdout = true,
+                "--bundle" => cli.bundle = true,
+                "-q" | "--quiet" => cli.quiet = true,
+                "-h" | "--help" => {
+                    return Ok(Cli {
+                        command: Command::Help,
+                        ..cli
+                    })
+                }
+                "-V" | "--version" => {
+                    return Ok(Cli {
+                        command: Command::Version,
+                        ..cli
+                    })
+                }
+                "-" => cli.input = Some(arg),
+                flag if flag.starts_with('-') => return Err(format!("unknown option '{flag}'")),
+                _ if command.is_none() => {
+                    command = Some(match arg.as_str() {
+                        "build" => Command::Build,
+                        "check" => Command::Check,
+                        "fmt" => Command::Fmt,
+                        "watch" => Command::Watch,
+                        "emit-ast" => Command::EmitAst,
+                        "emit-tokens" => Command::EmitTokens,
+                        // `copiler file.cop` builds a single file
+                        _ => {
+                            cli.input = Some(arg);
+                            Command::Build
+                        }
+                    });
+                }
+                _ if cli.input.is_none() => cli.input = Some(arg),
+                _ => return Err(format!("unexpected argument '{arg}'")),
+            }
+        }
+
+        match command {
+            Some(Command::Build) | None if cli.bundle => match cli.input.as_deref() {
+                None => Err("'--bundle' expects an entry file".into()),
+                Some("-") => Err("'--bundle' can't read the entry file from stdin".into()),
+                Some(_) => Ok(Cli {
+                    command: Command::Build,
+                    ..cli
+                }),
+            },
+            _ if cli.bundle => Err("'--bundle' only works with 'build'".into()),
+            Some(Command::Watch) if cli.input.is_some() => {
+                Err("'watch' works on the whole project and doesn't take a file".into())
+            }
+            Some(Command::EmitAst | Command::EmitTokens) if cli.input.is_none() => {
+                Err("expected a file to read, or '-' for stdin".into())
+            }
+            Some(command) => Ok(Cli { command, ..cli }),
+            None if cli.input.is_some() => Ok(Cli {
+                command: Command::Build,
+                ..cli
+            }),
+            None => Err("expected a command".into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Cli, String> {
+        Cli::parse(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn parses_commands_and_options() {
+        let cli = parse("build src/main.cop -o out.luau -q").unwrap();
+
+        assert_eq!(cli.command, Command::Build);
+        assert_eq!(cli.input.as_deref(), Some("src/main.cop"));
+        assert_eq!(cli.out, Some("out.luau".into()));
+        assert!(cli.quiet && !cli.stdout);
+
+        let cli = parse("check").unwrap();
+
+        assert_eq!(cli.command, Command::Check);
+        assert_eq!(cli.input, None);
+    }
+
+    #[test]
+    fn files_are_built_without_a_command() {
+        let cli = parse("main.cop --stdout").unwrap();
+
+        assert_eq!(cli.command, Command::Build);
+        assert_eq!(cli.input.as_deref(), Some("main.cop"));
+        assert!(cli.stdout);
+        assert_eq!(parse("build --help").unwrap().command, Command::Help);
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert_eq!(
+            parse("build --fast").unwrap_err(),
+            "unknown option '--fast'"
+        );
+        assert_eq!(parse("build -o").unwrap_err(), "'-o' expects a path");
+        assert_eq!(
+            parse("build a.cop b.cop").unwrap_err(),
+            "unexpected argument 'b.cop'"
+        );
+        assert!(parse("watch main.cop").is_err());
+        assert!(parse("emit-ast").is_err());
+        assert!(parse("check main.cop --bundle").is_err());
+        assert!(parse("build - --bundle").is_err());
+    }
+}
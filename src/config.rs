@@ -0,0 +1,312 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::{
+    backend::{Options, RequireStrategy, Target},
+    cache,
+    frontend::{
+        resolver::LINTS,
+        scripts::{CLIENT_APIS, SERVER_APIS},
+    },
+    project::Project,
+    rojo::Rojo,
+    util::diagnostic::LintLevel,
+};
+
+pub const FILE_NAME: &str = "copiler.toml";
+
+/// How imports are required, `{ custom = "..." }` replaces `{path}` in the
+/// given expression with the import path and `{name}` with its file name.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Require {
+    Relative,
+    Instance,
+    Custom(String),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Build {
+    pub src: PathBuf,
+    pub out: PathBuf,
+    pub target: Target,
+    pub strict: bool,
+    pub globals: Vec<String>,
+    /// Defaults to the instance paths of `default.project.json` when there is
+    /// one, to relative paths otherwise
+    pub require: Option<Require>,
+}
+
+impl Default for Build {
+    fn default() -> Self {
+        Self {
+            src: "src".into(),
+            out: "dist".into(),
+            target: Target::default(),
+            strict: false,
+            globals: vec![],
+            require: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Emit {
+    /// A comment written at the top of every output
+    pub header: Option<String>,
+    pub extension: String,
+}
+
+impl Default for Emit {
+    fn default() -> Self {
+        Self {
+            header: None,
+            extension: "luau".into(),
+        }
+    }
+}
+
+/// The APIs that only work on one side of the client-server boundary,
+/// replacing the default lists.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Apis {
+    pub client: Vec<String>,
+    pub server: Vec<String>,
+}
+
+impl Default for Apis {
+    fn default() -> Self {
+        Self {
+            client: CLIENT_APIS.iter().map(|api| api.to_string()).collect(),
+            server: SERVER_APIS.iter().map(|api| api.to_string()).collect(),
+        }
+    }
+}
+
+/// The contents of `copiler.toml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub build: Build,
+    pub lints: BTreeMap<String, LintLevel>,
+    pub emit: Emit,
+    pub apis: Apis,
+    /// The directory the config was found in, which paths are relative to
+    #[serde(skip)]
+    pub root: PathBuf,
+    /// The Rojo project next to the config
+    #[serde(skip)]
+    pub rojo: Option<Rojo>,
+}
+
+impl Config {
+    pub fn parse(src: &str, root: PathBuf) -> Result<Self, String> {
+        let config: Config = toml::from_str(src).map_err(|err| err.to_string())?;
+
+        if let Some(lint) = config
+            .lints
+            .keys()
+            .find(|lint| !LINTS.contains(&lint.as_str()))
+        {
+            return Err(format!(
+                "unknown lint '{lint}', expected one of {}",
+                LINTS.join(", ")
+            ));
+        }
+
+        Ok(Self { root, ..config })
+    }
+
+    /// Finds `copiler.toml` in `dir` or the closest parent directory that has
+    /// one, the default config rooted at `dir` when there isn't one.
+    pub fn discover(dir: &Path) -> Result<Self, String> {
+        for dir in dir.ancestors() {
+            let path = dir.join(FILE_NAME);
+
+            if path.is_file() {
+                let src = fs::read_to_string(&path).map_err(|err| err.to_string())?;
+                let config = Self::parse(&src, dir.into())
+                    .map_err(|err| format!("{}: {err}", path.display()))?;
+
+                return Ok(Self {
+                    rojo: Rojo::discover(dir)?,
+                    ..config
+                });
+            }
+        }
+
+        Ok(Self {
+            root: dir.into(),
+            rojo: Rojo::discover(dir)?,
+            ..Default::default()
+        })
+    }
+
+    pub fn options(&self) -> Options {
+        let require = match (&self.build.require, &self.rojo) {
+            (None, Some(rojo)) => {
+                let rojo = rojo.clone();
+                let project = self.project();
+
+                // Imports are required from where their output is synced to
+                RequireStrategy::File(Box::new(move |path| {
+                    let path = std::path::absolute(path).ok()?;
+                    path.strip_prefix(&project.src).ok()?;
+
+                    rojo.instance(&project.output(&path, None))
+                }))
+            }
+            (None | Some(Require::Relative), _) => RequireStrategy::Relative,
+            (Some(Require::Instance), _) => RequireStrategy::Instance,
+            (Some(Require::Custom(template)), _) => {
+                let template = template.clone();
+
+                RequireStrategy::Custom(Box::new(move |path| {
+                    let name = path.rsplit('/').next().unwrap();
+
+                    template.replace("{path}", path).replace("{name}", name)
+                }))
+            }
+        };
+
+        Options {
+            require,
+            target: self.build.target,
+            strict: self.build.strict,
+            globals: self.build.globals.clone(),
+            lints: self
+                .lints
+                .iter()
+                .map(|(lint, level)| (lint.clone(), *level))
+                .collect(),
+            client_apis: self.apis.client.clone(),
+            server_apis: self.apis.server.clone(),
+        }
+    }
+
+    pub fn project(&self) -> Project {
+        Project {
+            src: self.root.join(&self.build.src),
+            out: self.root.join(&self.build.out),
+            header: self.emit.header.clone(),
+            extension: self.emit.extension.clone(),
+            fingerprint: cache::hash(format!(
+                "{} {:?} {:?} {:?} {:?} {:?}",
+                env!("CARGO_PKG_VERSION"),
+                self.build,
+                self.lints,
+                self.emit,
+                self.apis,
+                self.rojo
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    #[test]
+    fn parses_every_section() {
+        let config = Config::parse(
+            r#"
+                [build]
+                src = "code"
+                target = "luau"
+                strict = true
+                require = { custom = "game.Modules.{name}" }
+
+                [lints]
+                shadowing = "error"
+            "#,
+            "game".into(),
+        )
+        .unwrap();
+
+        let options = config.options();
+
+        assert_eq!(config.project().src, Path::new("game/code"));
+        assert_eq!(config.project().out, Path::new("game/dist"));
+        assert_eq!(options.target, Target::Luau);
+        assert!(options.strict);
+        assert_eq!(options.lints["shadowing"], LintLevel::Error);
+        assert_eq!(
+            options.require.require_path(None, "./shared/vehicles"),
+            "game.Modules.vehicles"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_settings() {
+        let err = Config::parse("[lints]\nshadowed = \"warn\"", "".into()).unwrap_err();
+
+        assert!(err.starts_with("unknown lint 'shadowed'"), "{err}");
+        assert!(Config::parse("[build]\nsource = \"src\"", "".into()).is_err());
+    }
+
+    #[test]
+    fn settings_change_the_fingerprint() {
+        let default = Config::parse("", "".into()).unwrap();
+        let strict = Config::parse("[build]\nstrict = true", "".into()).unwrap();
+
+        assert_ne!(default.project().fingerprint, strict.project().fingerprint);
+    }
+
+    #[test]
+    fn discovers_the_closest_config() {
+        let dir = env::temp_dir().join(format!("copiler-config-{}", process::id()));
+
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src/server")).unwrap();
+        fs::write(dir.join(FILE_NAME), "[build]\nout = \"build\"").unwrap();
+
+        let config = Config::discover(&dir.join("src/server")).unwrap();
+
+        assert_eq!(config.root, dir);
+        assert_eq!(config.project().out, dir.join("build"));
+    }
+
+    #[test]
+    fn imports_follow_the_rojo_project() {
+        let dir = env::temp_dir().join(format!("copiler-config-rojo-{}", process::id()));
+
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src/server")).unwrap();
+        fs::write(
+            dir.join(crate::rojo::FILE_NAME),
+            r#"{
+                "name": "game",
+                "tree": {
+                    "$className": "DataModel",
+                    "ReplicatedStorage": { "Shared": { "$path": "dist/shared" } }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let options = Config::discover(&dir).unwrap().options();
+        let server = Some(dir.join("src/server"));
+
+        assert_eq!(
+            options
+                .require
+                .require_path(server.as_deref(), "../shared/vehicles"),
+            "game.ReplicatedStorage.Shared.vehicles"
+        );
+        assert_eq!(
+            options.require.require_path(server.as_deref(), "./util"),
+            "script.Parent.util"
+        );
+    }
+}
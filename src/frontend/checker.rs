@@ -0,0 +1,1458 @@
+use std::collections::HashMap;
+
+use crate::{
+    frontend::parser::{Expression, MatchArm, Operator, Pattern, Statement, Type},
+    util::diagnostic::{Diagnostic, Reporter},
+};
+
+const PRIMITIVES: [&str; 6] = ["number", "string", "boolean", "nil", "thread", "buffer"];
+
+/// The members of a class or interface.
+#[derive(Debug, Clone, Default)]
+struct Object {
+    fields: HashMap<String, Type>,
+    /// Method types, without the `self` parameter
+    methods: HashMap<String, Type>,
+    implements: Vec<String>,
+    interface: bool,
+}
+
+/// Infers the types of expressions and reports values that can't be
+/// assigned, passed or returned where they're used. Anything the checker
+/// can't infer is left to Luau.
+#[derive(Default)]
+pub struct Checker {
+    pub reporter: Reporter,
+    /// Adds the inferred types of unannotated `let` bindings to the output as
+    /// annotations. Turned on by the `strict` option or a leading `--!strict`
+    /// Luau block, it doesn't change what the checker reports
+    pub strict: bool,
+    scopes: Vec<HashMap<String, Option<Type>>>,
+    objects: HashMap<String, Object>,
+    /// Interface declarations, which the classes implementing them are
+    /// checked against
+    interfaces: HashMap<String, Statement>,
+    enums: Vec<String>,
+    aliases: HashMap<String, Type>,
+    return_types: Vec<Option<Type>>,
+    /// The types returned by each function being checked, used to infer
+    /// return types that aren't annotated
+    returned: Vec<Vec<Option<Type>>>,
+    class: Option<String>,
+}
+
+impl Checker {
+    fn any() -> Type {
+        Type::Named {
+            ident: "any".into(),
+            generics: vec![],
+        }
+    }
+
+    fn named(ident: &str) -> Type {
+        Type::Named {
+            ident: ident.into(),
+            generics: vec![],
+        }
+    }
+
+    fn is_primitive(ty: &Type) -> bool {
+        matches!(ty, Type::Named { ident, .. } if PRIMITIVES.contains(&ident.as_str()))
+    }
+
+    fn declare_variable(&mut self, ident: &str, ty: Option<Type>) {
+        self.scopes.last_mut().unwrap().insert(ident.into(), ty);
+    }
+
+    fn lookup(&self, ident: &str) -> Option<Type> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(ident))
+            .cloned()
+            .flatten()
+    }
+
+    /// The type of a literal, used where inferring a full expression would
+    /// report its diagnostics twice.
+    fn literal_type(expr: &Expression) -> Option<Type> {
+        match expr {
+            Expression::Number(_) => Some(Self::named("number")),
+            Expression::String(_) | Expression::Char(_) => Some(Self::named("string")),
+            Expression::Bool(_) => Some(Self::named("boolean")),
+            _ => None,
+        }
+    }
+
+    fn function_type(
+        generics: &[String],
+        params: &[Expression],
+        return_type: &Option<Type>,
+    ) -> Type {
+        let params = params
+            .iter()
+            .filter_map(|param| match param {
+                Expression::Parameter {
+                    ident,
+                    expected_type,
+                    default,
+                    variadic,
+                } => {
+                    let Expression::Identifier(ident) = ident.as_ref() else {
+                        return None;
+                    };
+
+                    let ty = expected_type
+                        .clone()
+                        .or_else(|| default.as_ref().as_ref().and_then(Self::literal_type))
+                        .unwrap_or_else(Self::any);
+
+                    let ty = if *variadic {
+                        Type::Variadic(Box::new(ty))
+                    } else if default.is_some() {
+                        Type::Optional(Box::new(ty))
+                    } else {
+                        ty
+                    };
+
+                    Some((Some(ident.clone()), ty))
+                }
+                _ => None,
+            })
+            .collect();
+
+        Type::Function {
+            generics: generics.to_vec(),
+            params,
+            returns: Box::new(return_type.clone().unwrap_or_else(Self::any)),
+        }
+    }
+
+    fn resolve(&self, ty: &Type) -> Type {
+        match ty {
+            Type::Named { ident, generics } if generics.is_empty() => {
+                match self.aliases.get(ident) {
+                    Some(alias) => self.resolve(alias),
+                    None => ty.clone(),
+                }
+            }
+            ty => ty.clone(),
+        }
+    }
+
+    /// Whether a value of type `actual` can be used where `expected` is
+    /// required. Types the checker doesn't know are always assignable.
+    fn is_assignable(&self, actual: &Type, expected: &Type) -> bool {
+        let actual = self.resolve(actual);
+        let expected = self.resolve(expected);
+        let nil = Self::named("nil");
+
+        match (&actual, &expected) {
+            (Type::Named { ident, .. }, _) | (_, Type::Named { ident, .. })
+                if ident == "any" || ident == "unknown" =>
+            {
+                true
+            }
+            (Type::Union(types), _) => types.iter().all(|ty| self.is_assignable(ty, &expected)),
+            (_, Type::Optional(expected)) => actual == nil || self.is_assignable(&actual, expected),
+            (Type::Optional(actual), _) => {
+                self.is_assignable(&nil, &expected) && self.is_assignable(actual, &expected)
+            }
+            (_, Type::Union(types)) => types.iter().any(|ty| self.is_assignable(&actual, ty)),
+            (Type::Intersection(types), _) => {
+                types.iter().any(|ty| self.is_assignable(ty, &expected))
+            }
+            (_, Type::Intersection(types)) => {
+                types.iter().all(|ty| self.is_assignable(&actual, ty))
+            }
+            (Type::Singleton(actual), Type::Singleton(expected)) => actual == expected,
+            (Type::Singleton(_), Type::Named { ident, .. })
+            | (Type::Named { ident, .. }, Type::Singleton(_)) => ident == "string",
+            (
+                Type::Named { ident: actual, .. },
+                Type::Named {
+                    ident: expected, ..
+                },
+            ) => {
+                if actual == expected {
+                    return true;
+                }
+
+                let known = |ident: &String| {
+                    PRIMITIVES.contains(&ident.as_str())
+                        || self.objects.contains_key(ident)
+                        || self.enums.contains(ident)
+                };
+
+                match (self.objects.get(actual), self.objects.get(expected)) {
+                    (Some(class), Some(interface)) if interface.interface => {
+                        class.implements.contains(expected)
+                    }
+                    _ => !(known(actual) && known(expected)),
+                }
+            }
+            (Type::Table { .. } | Type::Array(_) | Type::Function { .. }, Type::Named { .. })
+            | (Type::Named { .. }, Type::Table { .. } | Type::Array(_) | Type::Function { .. }) => {
+                !(Self::is_primitive(&actual) || Self::is_primitive(&expected))
+            }
+            (Type::Array(actual), Type::Array(expected)) => self.is_assignable(actual, expected),
+            _ => true,
+        }
+    }
+
+    fn members(&self, ty: &Type) -> Option<&Object> {
+        match self.resolve(ty) {
+            Type::Named { ident, .. } => self.objects.get(&ident),
+            _ => None,
+        }
+    }
+
+    /// Checks the arguments of a call against the parameters of the function
+    /// type it calls.
+    fn check_call(&mut self, function: &str, callee: &Type, args: &mut [Expression]) {
+        let Type::Function { params, .. } = callee else {
+            for arg in args {
+                self.infer(arg);
+            }
+
+            return;
+        };
+
+        let variadic = match params.last() {
+            Some((_, Type::Variadic(ty))) => Some(ty.as_ref().clone()),
+            _ => None,
+        };
+        let fixed = params.len() - usize::from(variadic.is_some());
+        let given = args.len();
+        let mut passed = vec![false; fixed];
+        let mut position = 0;
+
+        for arg in args {
+            let (param, value) = match arg {
+                Expression::NamedArgument { ident, value } => {
+                    let index = params.iter().position(|(name, _)| {
+                        matches!(ident.as_ref(), Expression::Identifier(ident) if Some(ident) == name.as_ref())
+                    });
+
+                    (index, value.as_mut())
+                }
+                arg => {
+                    position += 1;
+
+                    (Some(position - 1), arg)
+                }
+            };
+
+            let actual = self.infer(value);
+
+            let expected = match param {
+                Some(index) if index < fixed => {
+                    passed[index] = true;
+
+                    params[index].1.clone()
+                }
+                Some(_) if variadic.is_some() => variadic.clone().unwrap(),
+                Some(_) => {
+                    self.reporter.report(Diagnostic::error(format!(
+                        "'{function}' takes {fixed} argument(s) but {given} were given"
+                    )));
+
+                    return;
+                }
+                None => continue,
+            };
+
+            if let Some(actual) = actual {
+                if !self.is_assignable(&actual, &expected) {
+                    let param = match param {
+                        Some(index) if index < fixed => match &params[index].0 {
+                            Some(name) => format!("argument '{name}'"),
+                            None => format!("argument #{}", index + 1),
+                        },
+                        _ => "variadic argument".into(),
+                    };
+
+                    self.reporter.report(Diagnostic::error(format!(
+                        "{param} of '{function}' expects '{expected}' but got '{actual}'"
+                    )));
+                }
+            }
+        }
+
+        for (index, (name, ty)) in params.iter().take(fixed).enumerate() {
+            if !passed[index] && !self.is_assignable(&Self::named("nil"), ty) {
+                let name = name.clone().unwrap_or_else(|| format!("#{}", index + 1));
+
+                self.reporter.report(Diagnostic::error(format!(
+                    "missing argument '{name}' of type '{ty}' in call to '{function}'"
+                )));
+            }
+        }
+    }
+
+    fn check_operation(
+        &mut self,
+        operator: Operator,
+        l: &mut Expression,
+        r: &mut Expression,
+    ) -> Option<Type> {
+        let l = self.infer(l);
+        let r = self.infer(r);
+
+        if matches!(operator, Operator::Eq | Operator::Ne) {
+            return Some(Self::named("boolean"));
+        }
+
+        let number = Self::named("number");
+
+        for side in [&l, &r].into_iter().flatten() {
+            let comparable = operator.is_comparison() && *side == Self::named("string");
+
+            if Self::is_primitive(side) && *side != number && !comparable {
+                self.reporter.report(Diagnostic::error(format!(
+                    "operator '{}' can't be applied to '{side}'",
+                    operator.symbol()
+                )));
+
+                return None;
+            }
+        }
+
+        if operator.is_comparison() {
+            if let (Some(l), Some(r)) = (&l, &r) {
+                if Self::is_primitive(l) && Self::is_primitive(r) && l != r {
+                    self.reporter
+                        .report(Diagnostic::error(format!("can't compare '{l}' with '{r}'")));
+                }
+            }
+
+            return Some(Self::named("boolean"));
+        }
+
+        (l.as_ref() == Some(&number) && r.as_ref() == Some(&number)).then_some(number)
+    }
+
+    /// Infers the type of an expression, checking any calls and operations
+    /// inside of it.
+    fn infer(&mut self, expr: &mut Expression) -> Option<Type> {
+        match expr {
+            Expression::Number(_)
+            | Expression::String(_)
+            | Expression::Char(_)
+            | Expression::Bool(_) => Self::literal_type(expr),
+            Expression::Identifier(ident) if ident == "nil" => Some(Self::named("nil")),
+            Expression::Identifier(ident) if ident == "self" && self.class.is_some() => {
+                self.class.as_deref().map(Self::named)
+            }
+            Expression::Identifier(ident) => self.lookup(ident),
+            Expression::Indexing(l, r) => match (l.as_mut(), r.as_ref()) {
+                (Expression::Identifier(ident), Expression::Identifier(_))
+                    if self.enums.contains(ident) =>
+                {
+                    Some(Self::named(ident))
+                }
+                (l, Expression::Identifier(member)) => {
+                    let object = self.infer(l)?;
+                    let object = self.members(&object)?;
+
+                    object
+                        .fields
+                        .get(member)
+                        .or_else(|| object.methods.get(member))
+                        .cloned()
+                }
+                (l, _) => {
+                    self.infer(l);
+
+                    None
+                }
+            },
+            Expression::FunctionCall { ident, args } => {
+                if let Expression::Indexing(l, r) = ident.as_ref() {
+                    if let (Expression::Identifier(l), Expression::Identifier(r)) =
+                        (l.as_ref(), r.as_ref())
+                    {
+                        if r == "new" && self.objects.get(l).is_some_and(|object| !object.interface)
+                        {
+                            return Some(Self::named(l));
+                        }
+
+                        if self.enums.contains(l) {
+                            for arg in args {
+                                self.infer(arg);
+                            }
+
+                            return Some(Self::named(l));
+                        }
+                    }
+                }
+
+                let function = Self::callee_name(ident);
+                let member = match ident.as_ref() {
+                    Expression::Indexing(_, r) => match r.as_ref() {
+                        Expression::Identifier(member) => Some(member.clone()),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+
+                // Methods called with `.` take the object as their first argument
+                let (callee, method) = match (member, ident.as_mut()) {
+                    (Some(member), Expression::Indexing(l, _)) => {
+                        let object = self.infer(l);
+                        let object = object.as_ref().and_then(|object| self.members(object));
+
+                        match object.and_then(|object| object.methods.get(&member)) {
+                            Some(method) => (Some(method.clone()), true),
+                            None => (
+                                object
+                                    .and_then(|object| object.fields.get(&member))
+                                    .cloned(),
+                                false,
+                            ),
+                        }
+                    }
+                    (_, ident) => (self.infer(ident), false),
+                };
+
+                let Some(callee) = callee else {
+                    for arg in args {
+                        self.infer(arg);
+                    }
+
+                    return None;
+                };
+
+                let args = if method && !args.is_empty() {
+                    let (first, rest) = args.split_at_mut(1);
+
+                    self.infer(&mut first[0]);
+
+                    rest
+                } else {
+                    &mut args[..]
+                };
+
+                self.check_call(&function, &callee, args);
+
+                Self::return_type(&callee)
+            }
+            Expression::MethodCall { ident, args } => {
+                let function = Self::callee_name(ident);
+                let callee = self.infer(ident);
+
+                if let Some(object) = args.first_mut() {
+                    self.infer(object);
+                }
+
+                match callee {
+                    Some(callee) => {
+                        self.check_call(&function, &callee, args.get_mut(1..).unwrap_or_default());
+
+                        Self::return_type(&callee)
+                    }
+                    None => {
+                        for arg in args.iter_mut().skip(1) {
+                            self.infer(arg);
+                        }
+
+                        None
+                    }
+                }
+            }
+            Expression::Function {
+                generics,
+                params,
+                return_type,
+                stmt,
+            } => {
+                // The body is checked even when the return type is annotated
+                let inferred = self.check_function(params, return_type, stmt);
+                let return_type = return_type.clone().or(inferred);
+
+                Some(Self::function_type(generics, params, &return_type))
+            }
+            Expression::And(l, r) => {
+                self.infer(l);
+                self.infer(r)
+            }
+            Expression::Or(l, r) => {
+                let l = self.infer(l);
+                let r = self.infer(r);
+
+                if l == r {
+                    l
+                } else {
+                    None
+                }
+            }
+            Expression::Operation(operator, l, r) => self.check_operation(*operator, l, r),
+            Expression::Negate(expr) => {
+                let ty = self.infer(expr)?;
+
+                if Self::is_primitive(&ty) && ty != Self::named("number") {
+                    self.reporter.report(Diagnostic::error(format!(
+                        "operator '-' can't be applied to '{ty}'"
+                    )));
+
+                    return None;
+                }
+
+                Some(ty)
+            }
+            Expression::Match { value, arms } => {
+                self.infer(value);
+                self.check_arms(arms);
+
+                None
+            }
+            Expression::NamedArgument { value, .. } => self.infer(value),
+            Expression::Parameter { .. }
+            | Expression::ClassBody { .. }
+            | Expression::EnumVariant { .. } => None,
+        }
+    }
+
+    fn callee_name(ident: &Expression) -> String {
+        match ident {
+            Expression::Identifier(ident) => ident.clone(),
+            Expression::Indexing(l, r) => {
+                format!("{}.{}", Self::callee_name(l), Self::callee_name(r))
+            }
+            _ => "function".into(),
+        }
+    }
+
+    /// The type a call to `callee` returns. Generic functions aren't
+    /// instantiated, so returns that mention their type parameters are unknown.
+    fn return_type(callee: &Type) -> Option<Type> {
+        match callee {
+            Type::Function {
+                generics, returns, ..
+            } if **returns != Self::any()
+                && !generics
+                    .iter()
+                    .any(|generic| Self::mentions(returns, generic)) =>
+            {
+                Some(returns.as_ref().clone())
+            }
+            _ => None,
+        }
+    }
+
+    fn mentions(ty: &Type, ident: &str) -> bool {
+        match ty {
+            Type::Named {
+                ident: name,
+                generics,
+            } => name == ident || generics.iter().any(|ty| Self::mentions(ty, ident)),
+            Type::Singleton(_) => false,
+            Type::Optional(ty) | Type::Array(ty) | Type::Variadic(ty) => Self::mentions(ty, ident),
+            Type::Union(types) | Type::Intersection(types) | Type::Tuple(types) => {
+                types.iter().any(|ty| Self::mentions(ty, ident))
+            }
+            Type::Function {
+                params, returns, ..
+            } => {
+                params.iter().any(|(_, ty)| Self::mentions(ty, ident))
+                    || Self::mentions(returns, ident)
+            }
+            Type::Table { fields, indexer } => {
+                fields.iter().any(|(_, ty)| Self::mentions(ty, ident))
+                    || indexer.as_ref().is_some_and(|(key, value)| {
+                        Self::mentions(key, ident) || Self::mentions(value, ident)
+                    })
+            }
+        }
+    }
+
+    /// Whether a type can be written in the generated Luau. Classes other
+    /// than generic ones don't export a type.
+    fn is_emittable(&self, ty: &Type) -> bool {
+        !self
+            .objects
+            .iter()
+            .any(|(ident, object)| !object.interface && Self::mentions(ty, ident))
+    }
+
+    /// Checks a function body, returning its return type when it isn't
+    /// annotated and every `return` gives a value of the same type.
+    fn check_function(
+        &mut self,
+        params: &mut [Expression],
+        return_type: &Option<Type>,
+        stmt: &mut Statement,
+    ) -> Option<Type> {
+        self.scopes.push(HashMap::new());
+
+        for param in params.iter_mut() {
+            if let Expression::Parameter {
+                ident,
+                expected_type,
+                default,
+                variadic,
+            } = param
+            {
+                let default_type = default
+                    .as_mut()
+                    .as_mut()
+                    .and_then(|default| self.infer(default));
+
+                if let (Some(expected_type), Some(default_type)) = (&*expected_type, &default_type)
+                {
+                    if !self.is_assignable(default_type, expected_type) {
+                        self.reporter.report(Diagnostic::error(format!(
+                            "default value of type '{default_type}' can't be assigned to parameter of type '{expected_type}'"
+                        )));
+                    }
+                }
+
+                if let Expression::Identifier(ident) = ident.as_ref() {
+                    let ty = expected_type.clone().or(default_type);
+                    let ty = if *variadic {
+                        Some(Type::Array(Box::new(ty.unwrap_or_else(Self::any))))
+                    } else {
+                        ty
+                    };
+
+                    self.declare_variable(ident, ty);
+                }
+            }
+        }
+
+        self.return_types.push(return_type.clone());
+        self.returned.push(vec![]);
+        self.check_statement(stmt);
+        self.return_types.pop();
+
+        self.scopes.pop();
+
+        let returned = self.returned.pop().unwrap();
+        let first = returned.first().cloned().flatten()?;
+
+        (return_type.is_none() && returned.iter().all(|ty| ty.as_ref() == Some(&first)))
+            .then_some(first)
+    }
+
+    fn check_arms(&mut self, arms: &mut [MatchArm]) {
+        for arm in arms {
+            self.scopes.push(HashMap::new());
+            self.declare_bindings(&arm.pattern);
+
+            if let Some(guard) = &mut arm.guard {
+                self.infer(guard);
+            }
+
+            self.check_statement(&mut arm.body);
+            self.scopes.pop();
+        }
+    }
+
+    /// Declares the names a pattern binds, with unknown types so they shadow
+    /// outer variables.
+    fn declare_bindings(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Identifier(ident) => self.declare_variable(ident, None),
+            Pattern::Variant { fields, .. } => {
+                for field in fields {
+                    self.declare_bindings(field);
+                }
+            }
+            Pattern::Table(fields) => {
+                for (field, pattern) in fields {
+                    match pattern {
+                        Some(pattern) => self.declare_bindings(pattern),
+                        None => self.declare_variable(field, None),
+                    }
+                }
+            }
+            Pattern::Array { items, rest } => {
+                for item in items {
+                    self.declare_bindings(item);
+                }
+
+                if let Some(Some(rest)) = rest {
+                    self.declare_variable(rest, None);
+                }
+            }
+            Pattern::Or(patterns) => {
+                for pattern in patterns {
+                    self.declare_bindings(pattern);
+                }
+            }
+            Pattern::Wildcard | Pattern::Literal(_) | Pattern::Range { .. } => {}
+        }
+    }
+
+    fn check_assignment(&mut self, target: &str, actual: Option<Type>, expected: Option<Type>) {
+        if let (Some(actual), Some(expected)) = (actual, expected) {
+            if !self.is_assignable(&actual, &expected) {
+                self.reporter.report(Diagnostic::error(format!(
+                    "can't assign '{actual}' to {target} of type '{expected}'"
+                )));
+            }
+        }
+    }
+
+    fn check_class(&mut self, ident: &str, body: &mut Expression) {
+        let Expression::ClassBody {
+            properties,
+            methods,
+            getters,
+            setters,
+            observers,
+            metamethods,
+        } = body
+        else {
+            return;
+        };
+
+        for property in properties {
+            if let Statement::VariableDeclaration {
+                ident: field,
+                value_type,
+                value,
+            } = property
+            {
+                self.reporter
+                    .enter(format!("{ident}.{}", Self::callee_name(field)));
+                let actual = self.infer(value);
+                self.reporter.exit();
+
+                self.check_assignment(
+                    &format!("field '{ident}.{}'", Self::callee_name(field)),
+                    actual,
+                    value_type.clone(),
+                );
+            }
+        }
+
+        let class = self.class.replace(ident.into());
+
+        for member in methods
+            .iter_mut()
+            .chain(getters.iter_mut())
+            .chain(setters.iter_mut())
+            .chain(observers.iter_mut())
+            .chain(metamethods.iter_mut())
+        {
+            if let Statement::VariableDeclaration {
+                ident: member,
+                value:
+                    Expression::Function {
+                        generics,
+                        params,
+                        return_type,
+                        stmt,
+                    },
+                ..
+            } = member
+            {
+                let member = Self::callee_name(member);
+
+                self.reporter.enter(format!("{ident}.{member}"));
+                let returned = self.check_function(params, return_type, stmt);
+                self.reporter.exit();
+
+                if let (Some(returned), Some(method)) = (
+                    returned,
+                    self.objects
+                        .get_mut(ident)
+                        .and_then(|object| object.methods.get_mut(&member)),
+                ) {
+                    *method = Self::function_type(generics, params, &Some(returned));
+                }
+            }
+        }
+
+        self.class = class;
+    }
+
+    /// Reports members of the implemented interfaces that a class is missing,
+    /// or declares with types the interface doesn't accept.
+    fn check_implements(&mut self, class: &str, implements: &[Expression], body: &Expression) {
+        let Expression::ClassBody {
+            properties,
+            methods,
+            getters,
+            ..
+        } = body
+        else {
+            return;
+        };
+
+        let fields = self
+            .objects
+            .get(class)
+            .map(|object| object.fields.clone())
+            .unwrap_or_default();
+        let properties = properties
+            .iter()
+            .chain(getters)
+            .filter_map(|property| match property {
+                Statement::VariableDeclaration { ident, .. } => Some(Self::callee_name(ident)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let methods = methods
+            .iter()
+            .filter_map(|method| match method {
+                Statement::VariableDeclaration {
+                    ident,
+                    value:
+                        Expression::Function {
+                            params,
+                            return_type,
+                            ..
+                        },
+                    ..
+                } => Some((Self::callee_name(ident), (params, return_type))),
+                _ => None,
+            })
+            .collect::<HashMap<_, _>>();
+
+        let parameter = |param: &Expression| match param {
+            Expression::Parameter {
+                ident,
+                expected_type,
+                ..
+            } => (Self::callee_name(ident), expected_type.clone()),
+            _ => (String::new(), None),
+        };
+
+        for interface in implements {
+            let interface = Self::callee_name(interface);
+
+            let Some(Statement::Interface {
+                properties: expected_properties,
+                methods: expected_methods,
+                ..
+            }) = self.interfaces.get(&interface).cloned()
+            else {
+                self.reporter.report(Diagnostic::error(format!(
+                    "class '{class}' implements unknown interface '{interface}'"
+                )));
+
+                continue;
+            };
+
+            for property in &expected_properties {
+                let (property, expected_type) = parameter(property);
+
+                if !properties.contains(&property) {
+                    self.reporter.report(Diagnostic::error(format!(
+                        "class '{class}' is missing property '{property}' required by '{interface}'"
+                    )));
+
+                    continue;
+                }
+
+                if let (Some(actual), Some(expected)) = (fields.get(&property), expected_type) {
+                    if !self.is_assignable(actual, &expected) {
+                        self.reporter.report(Diagnostic::error(format!(
+                            "property '{class}.{property}' has type '{actual}' but '{interface}' expects '{expected}'"
+                        )));
+                    }
+                }
+            }
+
+            for method in &expected_methods {
+                let Statement::VariableDeclaration {
+                    ident,
+                    value:
+                        Expression::Function {
+                            params: expected_params,
+                            return_type: expected_return_type,
+                            ..
+                        },
+                    ..
+                } = method
+                else {
+                    continue;
+                };
+
+                let method = Self::callee_name(ident);
+
+                let Some((params, return_type)) = methods.get(&method) else {
+                    self.reporter.report(Diagnostic::error(format!(
+                        "class '{class}' is missing method '{method}' required by '{interface}'"
+                    )));
+
+                    continue;
+                };
+
+                if params.len() != expected_params.len() {
+                    self.reporter.report(Diagnostic::error(format!(
+                        "method '{class}.{method}' takes {} parameter(s) but '{interface}' expects {}",
+                        params.len(),
+                        expected_params.len()
+                    )));
+
+                    continue;
+                }
+
+                for (param, expected) in params.iter().zip(expected_params) {
+                    let (param, param_type) = parameter(param);
+
+                    // The method is called with what the interface passes it
+                    if let (Some(param_type), (_, Some(expected_type))) =
+                        (param_type, parameter(expected))
+                    {
+                        if !self.is_assignable(&expected_type, &param_type) {
+                            self.reporter.report(Diagnostic::error(format!(
+                                "parameter '{param}' of '{class}.{method}' has type '{param_type}' but '{interface}' expects '{expected_type}'"
+                            )));
+                        }
+                    }
+                }
+
+                if let (Some(return_type), Some(expected_return_type)) =
+                    (return_type, expected_return_type)
+                {
+                    if !self.is_assignable(return_type, expected_return_type) {
+                        self.reporter.report(Diagnostic::error(format!(
+                            "method '{class}.{method}' returns '{return_type}' but '{interface}' expects '{expected_return_type}'"
+                        )));
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_statement(&mut self, stmt: &mut Statement) {
+        match stmt {
+            Statement::VariableDeclaration {
+                ident: Expression::Identifier(ident),
+                value_type,
+                value,
+            }
+            | Statement::Constant {
+                ident: Expression::Identifier(ident),
+                value_type,
+                value,
+            } => {
+                let ident = ident.clone();
+
+                if let Expression::Function {
+                    generics,
+                    params,
+                    return_type,
+                    ..
+                } = value
+                {
+                    let function = Self::function_type(generics, params, return_type);
+
+                    self.declare_variable(&ident, Some(value_type.clone().unwrap_or(function)));
+                }
+
+                // Expression statements are declarations of '_'
+                if ident == "_" {
+                    self.infer(value);
+                    return;
+                }
+
+                self.reporter.enter(ident.clone());
+                let actual = self.infer(value);
+                self.reporter.exit();
+
+                self.check_assignment(&format!("'{ident}'"), actual.clone(), value_type.clone());
+
+                // A variable that starts out as nil can later hold anything
+                let actual = actual.filter(|actual| *actual != Self::named("nil"));
+
+                self.declare_variable(&ident, value_type.clone().or(actual.clone()));
+
+                if self.strict
+                    && value_type.is_none()
+                    && !matches!(value, Expression::Function { .. })
+                {
+                    *value_type = actual.filter(|actual| self.is_emittable(actual));
+                }
+            }
+            Statement::VariableDeclaration { ident, value, .. }
+            | Statement::Constant { ident, value, .. }
+            | Statement::VariableAssignment { ident, value } => {
+                let name = Self::callee_name(ident);
+                let actual = self.infer(value);
+                let expected = match ident {
+                    Expression::Identifier(ident) => self.lookup(ident),
+                    ident => self.infer(ident),
+                };
+
+                self.check_assignment(&format!("'{name}'"), actual, expected);
+            }
+            Statement::Return(expr) => {
+                let actual = self.infer(expr);
+                let expected = self.return_types.last().cloned().flatten();
+
+                if let Some(returned) = self.returned.last_mut() {
+                    returned.push(actual.clone());
+                }
+
+                if let (Some(actual), Some(expected)) = (actual, expected) {
+                    if !self.is_assignable(&actual, &expected) {
+                        self.reporter.report(Diagnostic::error(format!(
+                            "can't return '{actual}' from a function that returns '{expected}'"
+                        )));
+                    }
+                }
+            }
+            Statement::If { expr, body } => {
+                self.infer(expr);
+
+                self.scopes.push(HashMap::new());
+                self.check_statement(body);
+                self.scopes.pop();
+            }
+            Statement::Scope(statements) => {
+                self.scopes.push(HashMap::new());
+
+                for stmt in statements.iter() {
+                    self.declare(stmt);
+                }
+
+                for stmt in statements {
+                    self.check_statement(stmt);
+                }
+
+                self.scopes.pop();
+            }
+            Statement::ClassConstructor {
+                ident,
+                implements,
+                body,
+                ..
+            } => {
+                let ident = Self::callee_name(ident);
+
+                self.check_implements(&ident, implements, body);
+                self.check_class(&ident, body);
+            }
+            Statement::Match { value, arms } => {
+                self.infer(value);
+                self.check_arms(arms);
+            }
+            Statement::Export(stmt) => self.check_statement(stmt),
+            Statement::Import { idents, .. } => {
+                for ident in idents {
+                    self.declare_variable(ident, None);
+                }
+            }
+            Statement::Interface { .. }
+            | Statement::Enum { .. }
+            | Statement::TypeAlias { .. }
+            | Statement::Annotation(_)
+            | Statement::Luau(_) => {}
+        }
+    }
+
+    /// Registers the types a statement declares so they can be used anywhere
+    /// in its scope.
+    fn declare(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::ClassConstructor {
+                ident,
+                implements,
+                body:
+                    Expression::ClassBody {
+                        properties,
+                        methods,
+                        getters,
+                        setters,
+                        ..
+                    },
+                ..
+            } => {
+                let mut object = Object {
+                    implements: implements.iter().map(Self::callee_name).collect(),
+                    ..Default::default()
+                };
+
+                for property in properties {
+                    if let Statement::VariableDeclaration {
+                        ident,
+                        value_type,
+                        value,
+                    } = property
+                    {
+                        if let Some(ty) = value_type.clone().or_else(|| Self::literal_type(value)) {
+                            object.fields.insert(Self::callee_name(ident), ty);
+                        }
+                    }
+                }
+
+                for accessor in getters.iter().chain(setters) {
+                    if let Statement::VariableDeclaration {
+                        ident,
+                        value:
+                            Expression::Function {
+                                params,
+                                return_type,
+                                ..
+                            },
+                        ..
+                    } = accessor
+                    {
+                        let ty = return_type.clone().or_else(|| match params.first() {
+                            Some(Expression::Parameter { expected_type, .. }) => {
+                                expected_type.clone()
+                            }
+                            _ => None,
+                        });
+
+                        if let Some(ty) = ty {
+                            object.fields.insert(Self::callee_name(ident), ty);
+                        }
+                    }
+                }
+
+                for method in methods {
+                    if let Statement::VariableDeclaration {
+                        ident,
+                        value:
+                            Expression::Function {
+                                generics,
+                                params,
+                                return_type,
+                                ..
+                            },
+                        ..
+                    } = method
+                    {
+                        object.methods.insert(
+                            Self::callee_name(ident),
+                            Self::function_type(generics, params, return_type),
+                        );
+                    }
+                }
+
+                self.objects.insert(Self::callee_name(ident), object);
+            }
+            Statement::Interface {
+                ident,
+                properties,
+                methods,
+            } => {
+                let mut object = Object {
+                    interface: true,
+                    ..Default::default()
+                };
+
+                self.interfaces
+                    .insert(Self::callee_name(ident), stmt.clone());
+
+                for property in properties {
+                    if let Expression::Parameter {
+                        ident,
+                        expected_type: Some(expected_type),
+                        ..
+                    } = property
+                    {
+                        object
+                            .fields
+                            .insert(Self::callee_name(ident), expected_type.clone());
+                    }
+                }
+
+                for method in methods {
+                    if let Statement::VariableDeclaration {
+                        ident,
+                        value:
+                            Expression::Function {
+                                generics,
+                                params,
+                                return_type,
+                                ..
+                            },
+                        ..
+                    } = method
+                    {
+                        object.methods.insert(
+                            Self::callee_name(ident),
+                            Self::function_type(generics, params, return_type),
+                        );
+                    }
+                }
+
+                self.objects.insert(Self::callee_name(ident), object);
+            }
+            Statement::Enum { ident, .. } => self.enums.push(Self::callee_name(ident)),
+            Statement::TypeAlias {
+                ident,
+                generics,
+                value,
+                ..
+            } if generics.is_empty() => {
+                self.aliases.insert(ident.clone(), value.clone());
+            }
+            Statement::Export(stmt) => self.declare(stmt),
+            _ => {}
+        }
+    }
+
+    pub fn run(&mut self, root_stmt: &mut Statement) {
+        let Statement::Scope(statements) = root_stmt else {
+            panic!("Root stmt must be a scope");
+        };
+
+        self.strict |= statements.iter().any(
+            |stmt| matches!(stmt, Statement::Luau(code) if code.trim_start().starts_with("--!strict")),
+        );
+
+        self.scopes.push(HashMap::new());
+
+        for stmt in statements.iter() {
+            self.declare(stmt);
+        }
+
+        for stmt in statements {
+            self.check_statement(stmt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::parser;
+
+    fn errors(src: &str) -> Vec<String> {
+        let mut stmt = parser::parse(src).unwrap();
+        let mut checker = Checker::default();
+
+        checker.run(&mut stmt);
+        checker
+            .reporter
+            .diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.is_error())
+            .map(|diagnostic| diagnostic.message.clone())
+            .collect()
+    }
+
+    #[test]
+    fn checks_bodies_of_functions_with_return_types() {
+        let src = r#"
+            let add = (a: number, b: number): number { return a + b }
+            let f = (): number {
+                add("x", 1)
+                let n: number = "s"
+                return 1
+            }
+            let g = (a: number): number { return "s" }
+        "#;
+
+        let errors = errors(src);
+
+        assert_eq!(errors.len(), 3, "{errors:#?}");
+    }
+
+    #[test]
+    fn accepts_functions_matching_their_return_types() {
+        let src = "let f = (a: number): number { return a * 2 }";
+
+        assert!(errors(src).is_empty());
+    }
+
+    #[test]
+    fn interfaces_accept_members_with_assignable_types() {
+        let src = r#"
+            type Health = number
+
+            interface Damageable {
+                health: Health
+                armor: number?
+                takeDamage(amount: Health): Health
+            }
+
+            class Npc implements Damageable {
+                let health: number = 100
+                let armor: nil | number = nil
+
+                let takeDamage = (amount: number): number {
+                    return self.health - amount
+                }
+            }
+        "#;
+
+        let errors = errors(src);
+
+        assert!(errors.is_empty(), "{errors:#?}");
+    }
+
+    #[test]
+    fn interfaces_reject_members_with_other_types() {
+        let src = r#"
+            interface Damageable {
+                health: number
+                takeDamage(amount: number)
+            }
+
+            class Npc implements Damageable {
+                let health: string = "full"
+
+                let takeDamage = (amount: string) {}
+            }
+        "#;
+
+        let errors = errors(src);
+
+        assert_eq!(errors.len(), 2, "{errors:#?}");
+    }
+
+    #[test]
+    fn classes_must_declare_every_interface_member() {
+        let src = r#"
+            interface Damageable {
+                health: number
+                takeDamage(amount: number)
+            }
+
+            class Npc implements Damageable, Missing {
+                let takeDamage = (amount: number, source: Instance) {}
+            }
+        "#;
+
+        assert_eq!(
+            errors(src),
+            [
+                "class 'Npc' is missing property 'health' required by 'Damageable'",
+                "method 'Npc.takeDamage' takes 2 parameter(s) but 'Damageable' expects 1",
+                "class 'Npc' implements unknown interface 'Missing'",
+            ]
+        );
+    }
+
+    #[test]
+    fn unnamed_parameters_are_numbered_in_errors() {
+        let src = r#"
+            let run = (f: (number, string) -> nil) {
+                f(1, 2)
+            }
+        "#;
+
+        assert_eq!(
+            errors(src),
+            ["in 'run': argument #2 of 'f' expects 'string' but got 'number'"]
+        );
+    }
+
+    #[test]
+    fn checks_calls_through_function_types() {
+        let src = r#"
+            let run = (f: (number) -> string) {
+                let s: string = f(1)
+                let n: number = f(1)
+                f("x")
+            }
+        "#;
+
+        assert_eq!(
+            errors(src),
+            [
+                "in 'run': can't assign 'string' to 'n' of type 'number'",
+                "in 'run': argument #1 of 'f' expects 'number' but got 'string'",
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_values_against_let_annotations() {
+        let src = r#"
+            let target: Model? = nil
+            let n: number = "s"
+            let m: number = 1
+            m = "x"
+        "#;
+
+        assert_eq!(
+            errors(src),
+            [
+                "can't assign 'string' to 'n' of type 'number'",
+                "can't assign 'string' to 'm' of type 'number'",
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_values_against_type_aliases() {
+        let src = r#"
+            type Id = number
+            let a: Id = 1
+            let b: Id = "s"
+        "#;
+
+        assert_eq!(errors(src), ["can't assign 'string' to 'b' of type 'Id'"]);
+    }
+
+    #[test]
+    fn accepts_any_argument_for_generic_parameters() {
+        let src = r#"
+            let id = <T>(x: T): T { return x }
+            let n: number = id(1)
+            let s: string = id("s")
+        "#;
+
+        let errors = errors(src);
+
+        assert!(errors.is_empty(), "{errors:#?}");
+    }
+
+    #[test]
+    fn checks_method_calls_and_operators() {
+        let src = r#"
+            class Car {
+                let speed = 0
+
+                let change_speed = (new: number) {
+                    self.speed = new
+                }
+            }
+
+            let car = Car.new()
+            car:change_speed("fast")
+            let s = "a" + 1
+            let t = -"x"
+        "#;
+
+        assert_eq!(
+            errors(src),
+            [
+                "argument 'new' of 'car.change_speed' expects 'number' but got 'string'",
+                "in 's': operator '+' can't be applied to 'string'",
+                "in 't': operator '-' can't be applied to 'string'",
+            ]
+        );
+    }
+
+    #[test]
+    fn leaves_unknown_values_to_luau() {
+        let src = r#"
+            let part = workspace.Part
+            let n: number = part.Size.X + game.Players.MaxPlayers
+            let s: string = part.Name
+        "#;
+
+        let errors = errors(src);
+
+        assert!(errors.is_empty(), "{errors:#?}");
+    }
+
+    #[test]
+    fn infers_return_types_from_return_statements() {
+        let src = r#"
+            let f = () { return 1 }
+            let n: string = f()
+            let count = f()
+            count = "s"
+        "#;
+
+        assert_eq!(
+            errors(src),
+            [
+                "can't assign 'number' to 'n' of type 'string'",
+                "can't assign 'string' to 'count' of type 'number'",
+            ]
+        );
+    }
+}
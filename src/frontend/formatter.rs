@@ -0,0 +1,93 @@
+/// Formats `.cop` source: lines are indented by four spaces per open
+/// bracket, trailing whitespace and repeated blank lines are removed, and
+/// the file ends with a single newline. Luau blocks are left as they are.
+pub fn format(src: &str) -> String {
+    let mut out = String::new();
+    let mut depth: usize = 0;
+    let mut luau = false;
+    let mut blank = true;
+
+    for line in src.lines() {
+        let trimmed = line.trim();
+        let markers = trimmed.matches("\\\\").count();
+
+        // Inside a Luau block, only the line closing it is touched
+        if luau && markers == 0 {
+            out += line.trim_end();
+            out.push('\n');
+            continue;
+        }
+
+        if markers % 2 == 1 {
+            luau = !luau;
+        }
+
+        if trimmed.is_empty() {
+            if !blank {
+                out.push('\n');
+            }
+
+            blank = true;
+            continue;
+        }
+
+        let (opened, closed, leading) = brackets(trimmed);
+        let indent = depth.saturating_sub(leading);
+
+        out += &"    ".repeat(indent);
+        out += trimmed;
+        out.push('\n');
+
+        depth = (depth + opened).saturating_sub(closed);
+        blank = false;
+    }
+
+    let out = out.trim_end();
+
+    if out.is_empty() {
+        String::new()
+    } else {
+        format!("{out}\n")
+    }
+}
+
+/// Counts the brackets a line opens and closes outside of strings and Luau
+/// code, and how many it starts by closing.
+fn brackets(line: &str) -> (usize, usize, usize) {
+    let mut opened = 0;
+    let mut closed = 0;
+    let mut leading = 0;
+    let mut quote = None;
+    let mut start = true;
+
+    // Code between `\\` markers on the same line is Luau
+    for (index, part) in line.split("\\\\").enumerate() {
+        if index % 2 == 1 {
+            start = false;
+            continue;
+        }
+
+        for char in part.chars() {
+            match (quote, char) {
+                (Some(open), char) if char == open => quote = None,
+                (Some(_), _) => {}
+                (None, '"' | '\'') => quote = Some(char),
+                (None, '{' | '(' | '[') => opened += 1,
+                (None, '}' | ')' | ']') => {
+                    if start && opened == 0 {
+                        leading += 1;
+                    }
+
+                    closed += 1;
+                }
+                _ => {}
+            }
+
+            if !matches!(char, '}' | ')' | ']') && !char.is_whitespace() {
+                start = false;
+            }
+        }
+    }
+
+    (opened, closed, leading)
+}
@@ -1,4 +1,4 @@
-use crate::util::cursor::Cursor;
+use crate::util::{cursor::Cursor, diagnostic::Diagnostic};
 
 #[derive(PartialEq, Clone, Debug)]
 pub enum Token {
@@ -17,6 +17,29 @@ pub enum Token {
     LParen,
     RParen,
     Dot,
+
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Caret,
+    DoubleEqual,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    FatArrow,
+    ThinArrow,
+    Pipe,
+    Ampersand,
+    Question,
+    DotDot,
+    Ellipsis,
+    LBracket,
+    RBracket,
+    At,
 }
 
 #[derive(PartialEq, Clone, Debug)]
@@ -31,15 +54,16 @@ pub enum Literal {
 pub struct Lexer {
     cursor: Cursor<char>,
     tokens: Vec<Token>,
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 impl<'lexer> Lexer {
-    fn identifier(&mut self) {
+    fn identifier(&mut self) -> Result<(), String> {
         let mut buffer = String::from(self.cursor.eat().unwrap());
 
         while self
             .cursor
-            .peek_iff(None, |char| char.is_alphabetic() || char == '_')
+            .peek_iff(None, |char| char.is_alphanumeric() || char == '_')
             .is_some()
         {
             buffer.push(self.cursor.eat().unwrap());
@@ -49,23 +73,37 @@ impl<'lexer> Lexer {
             self.tokens
                 .push(Token::Literal(Literal::Bool(buffer == "true")));
         } else if buffer == "and" {
-            panic!("Did you mean to use '&' instead of 'and'?");
+            return Err("Did you mean to use '&' instead of 'and'?".into());
         } else {
             self.tokens.push(Token::Identifier(buffer));
         }
+
+        Ok(())
     }
 
     fn number(&mut self) {
         let mut buffer = String::from(self.cursor.eat().unwrap());
 
-        while let Some(char) = self.cursor.eat_iff(|char| char.is_numeric() || char == '.') {
-            buffer.push(char);
+        loop {
+            // a '.' that isn't followed by a digit starts a range like `1..5`
+            let fraction = matches!(self.cursor.peek(None), Some('.'))
+                && matches!(self.cursor.peek(Some(2)), Some(char) if char.is_numeric());
+
+            if let Some(char) = self.cursor.eat_iff(|char| char.is_numeric()) {
+                buffer.push(char);
+            } else if fraction {
+                buffer.push(self.cursor.eat().unwrap());
+            } else {
+                break;
+            }
         }
 
         let float: f32 = buffer.parse().unwrap();
 
         if float.to_string() != buffer {
-            println!("Warning: '{}' turns into '{}'", buffer, float);
+            self.diagnostics.push(Diagnostic::warning(format!(
+                "'{buffer}' turns into '{float}'"
+            )));
         }
 
         self.tokens.push(Token::Literal(Literal::Number(float)));
@@ -86,35 +124,37 @@ impl<'lexer> Lexer {
         self.tokens.push(Token::Literal(Literal::String(buffer)));
     }
 
-    fn char(&mut self) {
+    fn char(&mut self) -> Result<(), String> {
         self.cursor.eat(); // '
 
-        let char = self.cursor.eat().unwrap();
+        let char = self.cursor.eat();
 
-        if self.cursor.eat_iff(|char| char == '\'').is_none() {
-            panic!("Expected ' after one character")
+        match (char, self.cursor.eat_iff(|char| char == '\'')) {
+            (Some(char), Some(_)) => self.tokens.push(Token::Literal(Literal::Char(char))),
+            _ => return Err("Expected ' after one character".into()),
         }
 
-        self.tokens.push(Token::Literal(Literal::Char(char)));
+        Ok(())
     }
 
-    fn next(&mut self) -> Option<&Token> {
+    fn next(&mut self) -> Result<(), String> {
         let char = self.cursor.peek(None);
-        let len = self.tokens.len();
 
         if let Some(char) = char {
             match char {
                 '_' | 'a'..='z' | 'A'..='Z' => {
-                    self.identifier();
+                    self.identifier()?;
                 }
                 '0'..='9' => {
                     self.number();
                 }
                 '"' => self.string(),
-                '\'' => self.char(),
+                '\'' => self.char()?,
                 '\\' => {
                     self.cursor.eat();
-                    self.cursor.eat_iff(|char| char == '\\').unwrap();
+                    self.cursor
+                        .eat_iff(|char| char == '\\')
+                        .ok_or("Expected '\\\\' to start a Luau block")?;
 
                     let mut buffer = String::new();
 
@@ -128,7 +168,11 @@ impl<'lexer> Lexer {
                             break;
                         }
 
-                        buffer += &self.cursor.eat().unwrap().to_string();
+                        buffer.push(
+                            self.cursor
+                                .eat()
+                                .ok_or("Expected '\\\\' to end the Luau block")?,
+                        );
                     }
 
                     self.tokens.push(Token::Luau(buffer));
@@ -154,7 +198,69 @@ impl<'lexer> Lexer {
                     self.cursor.eat();
                 }
                 '=' => {
-                    self.tokens.push(Token::Equal);
+                    self.cursor.eat();
+
+                    if self.cursor.eat_iff(|char| char == '=').is_some() {
+                        self.tokens.push(Token::DoubleEqual);
+                    } else if self.cursor.eat_iff(|char| char == '>').is_some() {
+                        self.tokens.push(Token::FatArrow);
+                    } else {
+                        self.tokens.push(Token::Equal);
+                    }
+                }
+                '!' => {
+                    self.cursor.eat();
+                    self.cursor
+                        .eat_iff(|char| char == '=')
+                        .ok_or("Expected '=' after '!'")?;
+
+                    self.tokens.push(Token::NotEqual);
+                }
+                '<' => {
+                    self.cursor.eat();
+
+                    if self.cursor.eat_iff(|char| char == '=').is_some() {
+                        self.tokens.push(Token::LessEqual);
+                    } else {
+                        self.tokens.push(Token::Less);
+                    }
+                }
+                '>' => {
+                    self.cursor.eat();
+
+                    if self.cursor.eat_iff(|char| char == '=').is_some() {
+                        self.tokens.push(Token::GreaterEqual);
+                    } else {
+                        self.tokens.push(Token::Greater);
+                    }
+                }
+                '+' => {
+                    self.tokens.push(Token::Plus);
+                    self.cursor.eat();
+                }
+                '-' => {
+                    self.cursor.eat();
+
+                    if self.cursor.eat_iff(|char| char == '>').is_some() {
+                        self.tokens.push(Token::ThinArrow);
+                    } else {
+                        self.tokens.push(Token::Minus);
+                    }
+                }
+                '*' => {
+                    self.tokens.push(Token::Star);
+                    self.cursor.eat();
+                }
+                '/' => {
+                    self.tokens.push(Token::Slash);
+                    self.cursor.eat();
+                }
+                '%' => {
+                    self.tokens.push(Token::Percent);
+                    self.cursor.eat();
+                }
+                '^' => {
+                    self.tokens.push(Token::Caret);
                     self.cursor.eat();
                 }
                 ':' => {
@@ -162,26 +268,55 @@ impl<'lexer> Lexer {
                     self.cursor.eat();
                 }
                 '.' => {
-                    self.tokens.push(Token::Dot);
+                    self.cursor.eat();
+
+                    if self.cursor.eat_iff(|char| char == '.').is_some() {
+                        if self.cursor.eat_iff(|char| char == '.').is_some() {
+                            self.tokens.push(Token::Ellipsis);
+                        } else {
+                            self.tokens.push(Token::DotDot);
+                        }
+                    } else {
+                        self.tokens.push(Token::Dot);
+                    }
+                }
+                '[' => {
+                    self.tokens.push(Token::LBracket);
+                    self.cursor.eat();
+                }
+                ']' => {
+                    self.tokens.push(Token::RBracket);
+                    self.cursor.eat();
+                }
+                '?' => {
+                    self.tokens.push(Token::Question);
+                    self.cursor.eat();
+                }
+                '@' => {
+                    self.tokens.push(Token::At);
                     self.cursor.eat();
                 }
                 '&' => {
                     self.cursor.eat();
-                    self.cursor.eat_iff(|char| char == '&').unwrap();
 
-                    self.tokens.push(Token::And);
-                    self.cursor.eat();
+                    if self.cursor.eat_iff(|char| char == '&').is_some() {
+                        self.tokens.push(Token::And);
+                    } else {
+                        self.tokens.push(Token::Ampersand);
+                    }
                 }
                 '|' => {
                     self.cursor.eat();
-                    self.cursor.eat_iff(|char| char == '|').unwrap();
 
-                    self.tokens.push(Token::Or);
-                    self.cursor.eat();
+                    if self.cursor.eat_iff(|char| char == '|').is_some() {
+                        self.tokens.push(Token::Or);
+                    } else {
+                        self.tokens.push(Token::Pipe);
+                    }
                 }
                 _ => {
                     if !char.is_whitespace() {
-                        panic!("Unexpected char: '{}'", char)
+                        return Err(format!("Unexpected char: '{char}'"));
                     }
 
                     self.cursor.eat();
@@ -189,18 +324,20 @@ impl<'lexer> Lexer {
             }
         }
 
-        if len != self.tokens.len() {
-            self.tokens.last()
-        } else {
-            None
-        }
+        Ok(())
     }
 
-    pub fn load(&'lexer mut self) -> &'lexer Vec<Token> {
+    /// The tokens of the source, or the first error in it. The tokens before
+    /// the error are still in `tokens`.
+    pub fn load(&'lexer mut self) -> Result<&'lexer Vec<Token>, String> {
         while self.cursor.peek(None).is_some() {
-            self.next();
+            self.next()?;
         }
 
+        Ok(&self.tokens)
+    }
+
+    pub fn tokens(&self) -> &[Token] {
         &self.tokens
     }
 
@@ -208,6 +345,41 @@ impl<'lexer> Lexer {
         let cursor = Cursor::new(src.chars().collect());
         let tokens = vec![];
 
-        Self { cursor, tokens }
+        Self {
+            cursor,
+            tokens,
+            diagnostics: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_that_lose_precision_are_warnings() {
+        let mut lexer = Lexer::new("let x = 16777217");
+
+        assert_eq!(
+            lexer.load().unwrap().last(),
+            Some(&Token::Literal(Literal::Number(16777216.0)))
+        );
+        assert_eq!(lexer.diagnostics.len(), 1);
+        assert!(!lexer.diagnostics[0].is_error());
+        assert_eq!(
+            lexer.diagnostics[0].message,
+            "'16777217' turns into '16777216'"
+        );
+    }
+
+    #[test]
+    fn unexpected_chars_are_errors() {
+        assert_eq!(
+            Lexer::new("let x = 1 # 2").load(),
+            Err("Unexpected char: '#'".into())
+        );
+        assert!(Lexer::new("let x = 'ab'").load().is_err());
+        assert!(Lexer::new("\\\\ print(1)").load().is_err());
     }
 }
@@ -1,2 +1,7 @@
+pub mod checker;
+pub mod formatter;
 pub mod lexer;
+pub mod modules;
 pub mod parser;
+pub mod resolver;
+pub mod scripts;
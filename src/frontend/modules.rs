@@ -0,0 +1,261 @@
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    frontend::{
+        parser::{self, Expression, Statement},
+        scripts::{script_kind, ScriptKind},
+    },
+    util::diagnostic::Diagnostic,
+};
+
+/// The file an import refers to, relative to the directory of the importing
+/// file. The `.cop` extension can be left out, `./config` also finds
+/// `config.module.cop`, and importing a directory imports its `init.cop`.
+pub fn resolve_import(dir: &Path, path: &str) -> PathBuf {
+    let mut resolved = PathBuf::new();
+
+    // `..` is resolved without touching the file system, so the same file
+    // always gets the same path
+    for component in dir.join(path).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if resolved.file_name().is_some() => {
+                resolved.pop();
+            }
+            component => resolved.push(component),
+        }
+    }
+
+    if resolved
+        .extension()
+        .is_some_and(|extension| extension == "cop")
+    {
+        return resolved;
+    }
+
+    if resolved.is_dir() {
+        return resolved.join("init.cop");
+    }
+
+    // Appended rather than set, `player.server` is `player.server.cop`
+    let mut path = resolved.into_os_string();
+    let mut module = path.clone();
+
+    path.push(".cop");
+    module.push(".module.cop");
+
+    let (path, module) = (PathBuf::from(path), PathBuf::from(module));
+
+    if !path.exists() && module.exists() {
+        module
+    } else {
+        path
+    }
+}
+
+/// The values a file exports. Exported interfaces and type aliases only
+/// exist as Luau types, so they can't be imported.
+pub fn exports(root_stmt: &Statement) -> Vec<String> {
+    let Statement::Scope(statements) = root_stmt else {
+        panic!("Root stmt must be a scope");
+    };
+
+    statements
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Statement::Export(stmt) => match stmt.as_ref() {
+                Statement::VariableDeclaration { ident, .. }
+                | Statement::Constant { ident, .. }
+                | Statement::ClassConstructor { ident, .. }
+                | Statement::Enum { ident, .. } => match ident {
+                    Expression::Identifier(ident) => Some(ident.clone()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+/// What the files importing a module rely on, they're checked again when it
+/// changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Signature {
+    pub exports: Vec<String>,
+    /// Only module scripts can be imported
+    pub kind: Option<ScriptKind>,
+}
+
+/// Parses a file for its signature, failing when it can't be read or parsed.
+pub fn read_signature(path: &Path) -> Result<Signature, String> {
+    let src = fs::read_to_string(path).map_err(|err| err.to_string())?;
+
+    match parser::parse(&src) {
+        Ok(root_stmt) => Ok(Signature {
+            exports: exports(&root_stmt),
+            kind: script_kind(&root_stmt, Some(path)).0,
+        }),
+        Err(error) => Err(error.message),
+    }
+}
+
+/// The paths a file imports, as written.
+pub fn imports(root_stmt: &Statement) -> Vec<String> {
+    let Statement::Scope(statements) = root_stmt else {
+        panic!("Root stmt must be a scope");
+    };
+
+    statements
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Statement::Import { path, .. } => Some(path.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Reports imports of files that don't exist, or of names those files don't
+/// export.
+pub fn check_imports(root_stmt: &Statement, dir: &Path) -> Vec<Diagnostic> {
+    let Statement::Scope(statements) = root_stmt else {
+        panic!("Root stmt must be a scope");
+    };
+
+    let mut diagnostics = vec![];
+
+    for stmt in statements {
+        let Statement::Import { idents, path } = stmt else {
+            continue;
+        };
+
+        let file = resolve_import(dir, path);
+
+        let Ok(src) = fs::read_to_string(&file) else {
+            diagnostics.push(Diagnostic::error(format!("can't find module '{path}'")));
+            continue;
+        };
+
+        // A module with a syntax error is reported when it's compiled itself
+        let Ok(root_stmt) = parser::parse(&src) else {
+            continue;
+        };
+
+        // Only module scripts return something to `require`
+        if let (Some(kind @ (ScriptKind::Server | ScriptKind::Client)), _) =
+            script_kind(&root_stmt, Some(&file))
+        {
+            diagnostics.push(Diagnostic::error(format!(
+                "'{path}' is a {} script, only modules can be imported",
+                kind.name()
+            )));
+            continue;
+        }
+
+        let exports = exports(&root_stmt);
+
+        for ident in idents {
+            if !exports.contains(ident) {
+                diagnostics.push(Diagnostic::error(format!(
+                    "module '{path}' has no export named '{ident}'"
+                )));
+            }
+        }
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    fn dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("copiler-modules-{name}-{}", process::id()));
+
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        dir
+    }
+
+    #[test]
+    fn imports_resolve_without_the_file_system() {
+        assert_eq!(
+            resolve_import(Path::new("src/server"), "../shared/./vehicles.cop"),
+            Path::new("src/shared/vehicles.cop")
+        );
+        assert_eq!(
+            resolve_import(Path::new("src"), "./player.server"),
+            Path::new("src/player.server.cop")
+        );
+    }
+
+    #[test]
+    fn imports_find_modules_and_init_files() {
+        let dir = dir("resolve");
+
+        fs::create_dir_all(dir.join("weapons")).unwrap();
+        fs::write(dir.join("config.module.cop"), "").unwrap();
+
+        assert_eq!(
+            resolve_import(&dir, "./config"),
+            dir.join("config.module.cop")
+        );
+        assert_eq!(
+            resolve_import(&dir, "./weapons"),
+            dir.join("weapons/init.cop")
+        );
+    }
+
+    #[test]
+    fn only_values_are_exported() {
+        let root_stmt = parser::parse(
+            "export class Car {}
+export const WHEELS = 4
+export type Id = number
+let hidden = 1
+",
+        )
+        .unwrap();
+
+        assert_eq!(exports(&root_stmt), ["Car", "WHEELS"]);
+    }
+
+    #[test]
+    fn reports_missing_modules_and_exports() {
+        let dir = dir("check");
+
+        fs::write(dir.join("vehicles.cop"), "export const WHEELS = 4").unwrap();
+        fs::write(dir.join("main.server.cop"), "print(1)").unwrap();
+
+        let root_stmt = parser::parse(
+            r#"import { WHEELS, Car } from "./vehicles"
+import { x } from "./missing"
+import { y } from "./main.server"
+"#,
+        )
+        .unwrap();
+
+        let errors = check_imports(&root_stmt, &dir)
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            errors,
+            [
+                "module './vehicles' has no export named 'Car'",
+                "can't find module './missing'",
+                "'./main.server' is a server script, only modules can be imported",
+            ]
+        );
+    }
+}
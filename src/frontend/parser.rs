@@ -1,4 +1,6 @@
-use crate::frontend::lexer::{Literal, Token};
+use std::fmt::Display;
+
+use crate::frontend::lexer::{Lexer, Literal, Token};
 use crate::util::cursor::Cursor;
 
 #[derive(Debug, Clone)]
@@ -9,12 +11,50 @@ pub enum Statement {
     },
     VariableDeclaration {
         ident: Expression,
+        value_type: Option<Type>,
+        value: Expression,
+    },
+    /// `const NAME = value`, a variable that can't be reassigned
+    Constant {
+        ident: Expression,
+        value_type: Option<Type>,
         value: Expression,
     },
     ClassConstructor {
         ident: Expression,
+        generics: Vec<String>,
+        implements: Vec<Expression>,
         body: Expression,
     },
+    Interface {
+        ident: Expression,
+        properties: Vec<Expression>,
+        methods: Vec<Statement>,
+    },
+    Enum {
+        ident: Expression,
+        variants: Vec<Expression>,
+    },
+    Match {
+        value: Expression,
+        arms: Vec<MatchArm>,
+    },
+    TypeAlias {
+        ident: String,
+        generics: Vec<String>,
+        value: Type,
+        export: bool,
+    },
+    /// `import { A, B } from "./path"`
+    Import {
+        idents: Vec<String>,
+        path: String,
+    },
+    /// A `let`, `const`, `class`, `enum` or `interface` declaration that other
+    /// files can import
+    Export(Box<Statement>),
+    /// `@server`, `@client` or `@module` at the top of a file
+    Annotation(String),
     Return(Expression),
     If {
         expr: Expression,
@@ -35,81 +75,711 @@ pub enum Expression {
         args: Vec<Expression>,
     },
     Function {
+        generics: Vec<String>,
         params: Vec<Expression>,
+        return_type: Option<Type>,
         stmt: Box<Statement>,
     },
     Parameter {
         ident: Box<Expression>,
-        expected_type: Box<Option<Expression>>,
+        expected_type: Option<Type>,
+        default: Box<Option<Expression>>,
+        variadic: bool,
+    },
+    NamedArgument {
+        ident: Box<Expression>,
+        value: Box<Expression>,
     },
     ClassBody {
         properties: Vec<Statement>,
         methods: Vec<Statement>,
+        getters: Vec<Statement>,
+        setters: Vec<Statement>,
+        observers: Vec<Statement>,
+        metamethods: Vec<Statement>,
+    },
+    EnumVariant {
+        ident: Box<Expression>,
+        fields: Vec<Expression>,
+    },
+    Match {
+        value: Box<Expression>,
+        arms: Vec<MatchArm>,
     },
     Identifier(String),
     Indexing(Box<Expression>, Box<Expression>),
     And(Box<Expression>, Box<Expression>),
     Or(Box<Expression>, Box<Expression>),
+    Operation(Operator, Box<Expression>, Box<Expression>),
+    Negate(Box<Expression>),
     String(String),
     Bool(bool),
     Char(char),
     Number(f32),
 }
 
+#[derive(Debug, Clone)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expression>,
+    pub body: Statement,
+}
+
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    Wildcard,
+    /// A bare name, either a unit enum variant or a binding for the whole value
+    Identifier(String),
+    Variant {
+        enum_ident: Option<String>,
+        ident: String,
+        fields: Vec<Pattern>,
+    },
+    Literal(Expression),
+    Range {
+        start: Expression,
+        end: Expression,
+        inclusive: bool,
+    },
+    /// `{ x, y: pattern }`, matching tables that have the listed keys
+    Table(Vec<(String, Option<Pattern>)>),
+    /// `[first, second, ..rest]`
+    Array {
+        items: Vec<Pattern>,
+        rest: Option<Option<String>>,
+    },
+    Or(Vec<Pattern>),
+}
+
+/// A type annotation, emitted as Luau type syntax.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    /// `number`, `Instance`, `Enum.Material` or `Map<string, T>`
+    Named {
+        ident: String,
+        generics: Vec<Type>,
+    },
+    /// A string literal type such as `"Idle"`
+    Singleton(String),
+    Optional(Box<Type>),
+    Union(Vec<Type>),
+    Intersection(Vec<Type>),
+    /// `(name: string, number) -> boolean` or `<T>(T) -> T`
+    Function {
+        generics: Vec<String>,
+        params: Vec<(Option<String>, Type)>,
+        returns: Box<Type>,
+    },
+    /// `{ x: number, [string]: boolean }`
+    Table {
+        fields: Vec<(String, Type)>,
+        indexer: Option<(Box<Type>, Box<Type>)>,
+    },
+    /// `{number}`
+    Array(Box<Type>),
+    /// A parenthesized list of types, used for multiple return values
+    Tuple(Vec<Type>),
+    /// `...number`
+    Variadic(Box<Type>),
+}
+
+impl Type {
+    /// Formats a type nested inside another one or used as a return type,
+    /// parenthesizing unions, intersections and function types.
+    pub fn grouped(&self) -> String {
+        match self {
+            Type::Union(_) | Type::Intersection(_) | Type::Function { .. } => format!("({self})"),
+            ty => ty.to_string(),
+        }
+    }
+}
+
+/// Formats a type as Luau type syntax.
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |types: &[Type], separator: &str, grouped: bool| {
+            types
+                .iter()
+                .map(|ty| {
+                    if grouped {
+                        ty.grouped()
+                    } else {
+                        ty.to_string()
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(separator)
+        };
+
+        match self {
+            Type::Named { ident, generics } if generics.is_empty() => write!(f, "{ident}"),
+            Type::Named { ident, generics } => {
+                write!(f, "{ident}<{}>", join(generics, ", ", false))
+            }
+            Type::Singleton(string) => write!(f, "\"{string}\""),
+            Type::Optional(ty) => write!(f, "{}?", ty.grouped()),
+            Type::Union(types) => write!(f, "{}", join(types, " | ", true)),
+            Type::Intersection(types) => write!(f, "{}", join(types, " & ", true)),
+            Type::Function {
+                generics,
+                params,
+                returns,
+            } => {
+                if !generics.is_empty() {
+                    write!(f, "<{}>", generics.join(", "))?;
+                }
+
+                let params = params
+                    .iter()
+                    .map(|(name, ty)| match name {
+                        Some(name) => format!("{name}: {ty}"),
+                        None => ty.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                write!(f, "({params}) -> {}", returns.grouped())
+            }
+            Type::Table { fields, indexer } => {
+                let mut entries = vec![];
+
+                if let Some((key, value)) = indexer {
+                    entries.push(format!("[{key}]: {value}"));
+                }
+
+                for (field, ty) in fields {
+                    entries.push(format!("{field}: {ty}"));
+                }
+
+                if entries.is_empty() {
+                    write!(f, "{{}}")
+                } else {
+                    write!(f, "{{ {} }}", entries.join(", "))
+                }
+            }
+            Type::Array(ty) => write!(f, "{{{ty}}}"),
+            Type::Tuple(types) => write!(f, "({})", join(types, ", ", false)),
+            Type::Variadic(ty) => write!(f, "...{}", ty.grouped()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Pow,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Operator {
+    pub fn from_token(token: &Token) -> Option<Self> {
+        Some(match token {
+            Token::Plus => Self::Add,
+            Token::Minus => Self::Sub,
+            Token::Star => Self::Mul,
+            Token::Slash => Self::Div,
+            Token::Percent => Self::Mod,
+            Token::Caret => Self::Pow,
+            Token::DoubleEqual => Self::Eq,
+            Token::NotEqual => Self::Ne,
+            Token::Less => Self::Lt,
+            Token::LessEqual => Self::Le,
+            Token::Greater => Self::Gt,
+            Token::GreaterEqual => Self::Ge,
+            _ => return None,
+        })
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+            Self::Mod => "%",
+            Self::Pow => "^",
+            Self::Eq => "==",
+            Self::Ne => "!=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+        }
+    }
+
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            Self::Eq | Self::Ne | Self::Lt | Self::Le | Self::Gt | Self::Ge
+        )
+    }
+
+    /// Binding power of the operator, matching Luau's precedence table. A
+    /// unary minus sits at 7, between '*' and '^'.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Self::Eq | Self::Ne | Self::Lt | Self::Le | Self::Gt | Self::Ge => 3,
+            Self::Add | Self::Sub => 5,
+            Self::Mul | Self::Div | Self::Mod => 6,
+            Self::Pow => 8,
+        }
+    }
+}
+
+/// Parsing fails with the message of the first syntax error.
+pub type ParseResult<T> = Result<T, String>;
+
+/// A file that couldn't be parsed, along with the top level statements
+/// before the error, so its imports are still known.
+#[derive(Debug)]
+pub struct SyntaxError {
+    pub message: String,
+    pub parsed: Box<Statement>,
+}
+
+/// Lexes and parses a source file.
+pub fn parse(src: &str) -> Result<Statement, SyntaxError> {
+    let mut lexer = Lexer::new(src);
+
+    match lexer.load() {
+        Ok(tokens) => Parser::new(tokens).load(),
+        Err(message) => {
+            // The tokens before the error still tell what the file imports
+            let parsed = Parser::new(lexer.tokens())
+                .load()
+                .map_or_else(|error| error.parsed, Box::new);
+
+            Err(SyntaxError { message, parsed })
+        }
+    }
+}
+
 pub struct Parser {
     cursor: Cursor<Token>,
 }
 
 impl Parser {
-    fn parse_anon_fn(&mut self) -> Option<Expression> {
+    /// Eats the next token when `handle` accepts it, failing with `message`
+    /// otherwise.
+    fn expect(&mut self, handle: fn(Token) -> bool, message: &str) -> ParseResult<Token> {
         self.cursor
-            .eat_iff(|token| matches!(token, Token::LParen))
-            .unwrap();
+            .eat_iff(handle)
+            .ok_or_else(|| message.to_string())
+    }
 
-        let mut args = vec![];
+    /// Parses a parenthesized parameter list.
+    fn parse_parameters(&mut self) -> ParseResult<Vec<Expression>> {
+        self.expect(|token| matches!(token, Token::LParen), "Expected '('")?;
 
-        while self
-            .cursor
-            .peek_iff(None, |token| !matches!(token, Token::RParen))
-            .is_some()
-        {
-            args.push(self.parse_parameter().unwrap());
+        let mut params = vec![];
 
-            if matches!(self.cursor.peek(None), Some(Token::Comma)) {
+        while let Some(param) = self.parse_parameter()? {
+            let variadic = matches!(param, Expression::Parameter { variadic: true, .. });
+
+            params.push(param);
+
+            if !variadic && matches!(self.cursor.peek(None), Some(Token::Comma)) {
                 self.cursor.eat();
             } else {
                 break;
             }
         }
 
-        self.cursor
-            .eat_iff(|token| matches!(token, Token::RParen))
-            .unwrap();
+        self.expect(
+            |token| matches!(token, Token::RParen),
+            "Expected ')' after parameters, a variadic parameter must come last",
+        )?;
 
-        self.cursor
-            .eat_iff(|token| matches!(token, Token::LScope))
-            .unwrap();
+        Ok(params)
+    }
 
-        let scope = self.parse_scope();
+    /// Parses an optional `<T, U>` list of generic parameters.
+    fn parse_generics(&mut self) -> ParseResult<Vec<String>> {
+        let mut generics = vec![];
 
-        self.cursor
-            .eat_iff(|token| matches!(token, Token::RScope))
-            .unwrap();
+        if self
+            .cursor
+            .eat_iff(|token| matches!(token, Token::Less))
+            .is_none()
+        {
+            return Ok(generics);
+        }
+
+        while let Some(Token::Identifier(generic)) = self
+            .cursor
+            .eat_iff(|token| matches!(token, Token::Identifier(_)))
+        {
+            generics.push(generic);
+
+            if self
+                .cursor
+                .eat_iff(|token| matches!(token, Token::Comma))
+                .is_none()
+            {
+                break;
+            }
+        }
+
+        self.expect(
+            |token| matches!(token, Token::Greater),
+            "Expected '>' after generic parameters",
+        )?;
 
+        Ok(generics)
+    }
+
+    /// Parses an optional `: Type` following a parameter list.
+    fn parse_return_type(&mut self) -> ParseResult<Option<Type>> {
         self.cursor
-            .eat_iff(|token| matches!(token, Token::RParen))
-            .unwrap();
+            .eat_iff(|token| matches!(token, Token::Colon))
+            .map(|_| self.parse_type())
+            .transpose()
+    }
 
-        Some(Expression::Function {
-            params: args,
+    /// Whether the '(' at the cursor starts a function literal rather than a
+    /// parenthesized expression, which is the case when the matching ')' is
+    /// followed by a return type or a body.
+    fn is_function(&self) -> bool {
+        let mut depth = 0;
+        let mut offset = 1;
+
+        while let Some(token) = self.cursor.peek(Some(offset)) {
+            match token {
+                Token::LParen => depth += 1,
+                Token::RParen if depth == 1 => {
+                    return matches!(
+                        self.cursor.peek(Some(offset + 1)),
+                        Some(Token::Colon | Token::LScope)
+                    );
+                }
+                Token::RParen => depth -= 1,
+                _ => {}
+            }
+
+            offset += 1;
+        }
+
+        false
+    }
+
+    /// Parses a function literal: a parameter list followed by its body.
+    fn parse_function(&mut self) -> ParseResult<Expression> {
+        let generics = self.parse_generics()?;
+        let params = self.parse_parameters()?;
+        let return_type = self.parse_return_type()?;
+
+        self.expect(|token| matches!(token, Token::LScope), "Expected '{'")?;
+
+        let scope = self.parse_scope()?;
+
+        self.expect(|token| matches!(token, Token::RScope), "Expected '}'")?;
+
+        Ok(Expression::Function {
+            generics,
+            params,
+            return_type,
             stmt: Box::new(scope),
         })
     }
 
-    fn parse_single_expression(&mut self) -> Option<Expression> {
+    fn parse_type(&mut self) -> ParseResult<Type> {
+        let mut types = vec![self.parse_intersection_type()?];
+
+        while self
+            .cursor
+            .eat_iff(|token| matches!(token, Token::Pipe))
+            .is_some()
+        {
+            types.push(self.parse_intersection_type()?);
+        }
+
+        Ok(if types.len() == 1 {
+            types.remove(0)
+        } else {
+            Type::Union(types)
+        })
+    }
+
+    fn parse_intersection_type(&mut self) -> ParseResult<Type> {
+        let mut types = vec![self.parse_optional_type()?];
+
+        while self
+            .cursor
+            .eat_iff(|token| matches!(token, Token::Ampersand))
+            .is_some()
+        {
+            types.push(self.parse_optional_type()?);
+        }
+
+        Ok(if types.len() == 1 {
+            types.remove(0)
+        } else {
+            Type::Intersection(types)
+        })
+    }
+
+    fn parse_optional_type(&mut self) -> ParseResult<Type> {
+        let mut ty = self.parse_single_type()?;
+
+        while self
+            .cursor
+            .eat_iff(|token| matches!(token, Token::Question))
+            .is_some()
+        {
+            ty = Type::Optional(Box::new(ty));
+        }
+
+        Ok(ty)
+    }
+
+    fn parse_single_type(&mut self) -> ParseResult<Type> {
+        if matches!(self.cursor.peek(None), Some(Token::Less)) {
+            let generics = self.parse_generics()?;
+
+            return match self.parse_single_type()? {
+                Type::Function {
+                    params, returns, ..
+                } => Ok(Type::Function {
+                    generics,
+                    params,
+                    returns,
+                }),
+                ty => Err(format!(
+                    "Expected function type after generic parameters, got {ty:?}"
+                )),
+            };
+        }
+
+        let ty = match self.cursor.eat() {
+            Some(Token::Identifier(mut ident)) => {
+                while self
+                    .cursor
+                    .eat_iff(|token| matches!(token, Token::Dot))
+                    .is_some()
+                {
+                    match self.cursor.eat() {
+                        Some(Token::Identifier(member)) => ident += &format!(".{member}"),
+                        token => {
+                            return Err(format!(
+                                "Expected type identifier after '.', got {token:?}"
+                            ))
+                        }
+                    }
+                }
+
+                let mut generics = vec![];
+
+                if self
+                    .cursor
+                    .eat_iff(|token| matches!(token, Token::Less))
+                    .is_some()
+                {
+                    loop {
+                        generics.push(self.parse_type()?);
+
+                        if self
+                            .cursor
+                            .eat_iff(|token| matches!(token, Token::Comma))
+                            .is_none()
+                        {
+                            break;
+                        }
+                    }
+
+                    self.expect(
+                        |token| matches!(token, Token::Greater),
+                        "Expected '>' after generic type arguments",
+                    )?;
+                }
+
+                Type::Named { ident, generics }
+            }
+            Some(Token::Literal(Literal::String(string))) => Type::Singleton(string),
+            Some(Token::Literal(Literal::Bool(bool))) => Type::Named {
+                ident: bool.to_string(),
+                generics: vec![],
+            },
+            Some(Token::Ellipsis) => Type::Variadic(Box::new(self.parse_optional_type()?)),
+            Some(Token::LScope) => self.parse_table_type()?,
+            Some(Token::LParen) => {
+                let mut params = vec![];
+
+                while !matches!(self.cursor.peek(None), Some(Token::RParen)) {
+                    let name = match (self.cursor.peek(None), self.cursor.peek(Some(2))) {
+                        (Some(Token::Identifier(name)), Some(Token::Colon)) => {
+                            self.cursor.eat();
+                            self.cursor.eat();
+
+                            Some(name)
+                        }
+                        _ => None,
+                    };
+
+                    params.push((name, self.parse_type()?));
+
+                    if self
+                        .cursor
+                        .eat_iff(|token| matches!(token, Token::Comma))
+                        .is_none()
+                    {
+                        break;
+                    }
+                }
+
+                self.expect(
+                    |token| matches!(token, Token::RParen),
+                    "Expected ')' after types",
+                )?;
+
+                if self
+                    .cursor
+                    .eat_iff(|token| matches!(token, Token::ThinArrow))
+                    .is_some()
+                {
+                    Type::Function {
+                        generics: vec![],
+                        params,
+                        returns: Box::new(self.parse_type()?),
+                    }
+                } else if params.len() == 1
+                    && params[0].0.is_none()
+                    && !matches!(params[0].1, Type::Variadic(_))
+                {
+                    params.remove(0).1
+                } else {
+                    if params.iter().any(|(name, _)| name.is_some()) {
+                        return Err(
+                            "Named types are only allowed in function type parameters".into()
+                        );
+                    }
+
+                    Type::Tuple(params.into_iter().map(|(_, ty)| ty).collect())
+                }
+            }
+            token => return Err(format!("Expected type, got {token:?}")),
+        };
+
+        Ok(ty)
+    }
+
+    /// Parses the inside of `{ ... }`, either an array type or a table of
+    /// fields with an optional indexer.
+    fn parse_table_type(&mut self) -> ParseResult<Type> {
+        let is_table = matches!(
+            (self.cursor.peek(None), self.cursor.peek(Some(2))),
+            (Some(Token::RScope), _)
+                | (Some(Token::LBracket), _)
+                | (Some(Token::Identifier(_)), Some(Token::Colon))
+        );
+
+        if !is_table {
+            let ty = self.parse_type()?;
+
+            self.expect(
+                |token| matches!(token, Token::RScope),
+                "Expected '}' after array type",
+            )?;
+
+            return Ok(Type::Array(Box::new(ty)));
+        }
+
+        let mut fields = vec![];
+        let mut indexer = None;
+
+        while let Some(token) = self.cursor.eat_iff(|token| !matches!(token, Token::RScope)) {
+            match token {
+                Token::LBracket => {
+                    let key = self.parse_type()?;
+
+                    self.expect(
+                        |token| matches!(token, Token::RBracket),
+                        "Expected ']' after indexer type",
+                    )?;
+                    self.expect(
+                        |token| matches!(token, Token::Colon),
+                        "Expected ':' after indexer",
+                    )?;
+
+                    if indexer.is_some() {
+                        return Err("A table type can only have one indexer".into());
+                    }
+
+                    indexer = Some((Box::new(key), Box::new(self.parse_type()?)));
+                }
+                Token::Identifier(field) => {
+                    self.expect(
+                        |token| matches!(token, Token::Colon),
+                        "Expected ':' after table type field",
+                    )?;
+
+                    fields.push((field, self.parse_type()?));
+                }
+                token => return Err(format!("Expected table type field, got {token:?}")),
+            }
+
+            if self
+                .cursor
+                .eat_iff(|token| matches!(token, Token::Comma))
+                .is_none()
+            {
+                break;
+            }
+        }
+
+        self.expect(
+            |token| matches!(token, Token::RScope),
+            "Expected '}' after table type",
+        )?;
+
+        Ok(Type::Table { fields, indexer })
+    }
+
+    fn parse_single_expression(&mut self) -> ParseResult<Option<Expression>> {
         if let Some(token) = self.cursor.peek(None) {
             let token = match token {
-                Token::LParen => return self.parse_anon_fn(),
+                Token::LParen if !self.is_function() => {
+                    self.cursor.eat();
+
+                    let expr = self
+                        .parse_binary_expression(1)?
+                        .ok_or("Expected expression after '('")?;
+
+                    self.expect(
+                        |token| matches!(token, Token::RParen),
+                        "Expected ')' after expression",
+                    )?;
+
+                    return Ok(Some(expr));
+                }
+                Token::LParen | Token::Less => return Ok(Some(self.parse_function()?)),
+                Token::Minus => {
+                    self.cursor.eat();
+
+                    // Only '^' binds tighter than a unary minus
+                    return Ok(Some(Expression::Negate(Box::new(
+                        self.parse_binary_expression(Operator::Pow.precedence())?
+                            .ok_or("Expected expression after '-'")?,
+                    ))));
+                }
+                Token::Identifier(identifier) if identifier == "match" => {
+                    self.cursor.eat();
+
+                    let (value, arms) = self.parse_match(true)?;
+
+                    return Ok(Some(Expression::Match {
+                        value: Box::new(value),
+                        arms,
+                    }));
+                }
                 Token::Identifier(identifier) => {
                     let identifier = Expression::Identifier(identifier);
 
@@ -120,7 +790,9 @@ impl Parser {
                         self.cursor.eat();
                         self.cursor.eat();
 
-                        let indexed = self.parse_expression().unwrap();
+                        let indexed = self
+                            .parse_single_expression()?
+                            .ok_or("Expected member after '.' or ':'")?;
 
                         match indexed.clone() {
                             Expression::FunctionCall {
@@ -129,28 +801,28 @@ impl Parser {
                                 if colon {
                                     args.insert(0, identifier.clone());
 
-                                    return Some(Expression::MethodCall {
+                                    return Ok(Some(Expression::MethodCall {
                                         ident: Box::new(Expression::Indexing(
                                             Box::new(identifier),
                                             ident,
                                         )),
                                         args,
-                                    });
+                                    }));
                                 } else {
-                                    return Some(Expression::FunctionCall {
+                                    return Ok(Some(Expression::FunctionCall {
                                         ident: Box::new(Expression::Indexing(
                                             Box::new(identifier),
                                             ident,
                                         )),
                                         args,
-                                    });
+                                    }));
                                 }
                             }
                             _ => {
-                                return Some(Expression::Indexing(
+                                return Ok(Some(Expression::Indexing(
                                     Box::new(identifier),
                                     Box::new(indexed),
-                                ));
+                                )));
                             }
                         }
                     } else if matches!(self.cursor.peek(Some(2)), Some(Token::LParen)) {
@@ -164,7 +836,24 @@ impl Parser {
                             .peek_iff(None, |token| !matches!(token, Token::RParen))
                             .is_some()
                         {
-                            args.push(self.parse_expression().unwrap());
+                            let arg = self.parse_expression()?.ok_or("Expected argument or ')'")?;
+
+                            if matches!(arg, Expression::Identifier(_))
+                                && self
+                                    .cursor
+                                    .eat_iff(|token| matches!(token, Token::Equal))
+                                    .is_some()
+                            {
+                                args.push(Expression::NamedArgument {
+                                    ident: Box::new(arg),
+                                    value: Box::new(
+                                        self.parse_expression()?
+                                            .ok_or("Expected value for named argument")?,
+                                    ),
+                                });
+                            } else {
+                                args.push(arg);
+                            }
 
                             if matches!(self.cursor.peek(None), Some(Token::Comma)) {
                                 self.cursor.eat();
@@ -193,89 +882,530 @@ impl Parser {
 
             if token.is_some() {
                 self.cursor.eat();
-
-                token
-            } else {
-                None
             }
+
+            Ok(token)
         } else {
-            None
+            Ok(None)
         }
     }
 
-    fn parse_expression(&mut self) -> Option<Expression> {
-        let single_expr = self.parse_single_expression();
+    fn parse_expression(&mut self) -> ParseResult<Option<Expression>> {
+        self.parse_binary_expression(0)
+    }
 
-        if let Some(ref l) = single_expr {
-            if self
-                .cursor
-                .eat_iff(|token| matches!(token, Token::And))
-                .is_some()
-            {
-                let r = self.parse_expression().unwrap();
-                Some(Expression::And(Box::new(l.clone()), Box::new(r)))
-            } else if self
-                .cursor
-                .eat_iff(|token| matches!(token, Token::Or))
-                .is_some()
-            {
-                let r = self.parse_expression().unwrap();
-                Some(Expression::Or(Box::new(l.clone()), Box::new(r)))
-            } else {
-                single_expr
+    fn parse_binary_expression(&mut self, min_precedence: u8) -> ParseResult<Option<Expression>> {
+        let Some(mut l) = self.parse_single_expression()? else {
+            return Ok(None);
+        };
+
+        while let Some(token) = self.cursor.peek(None) {
+            let operator = Operator::from_token(&token);
+            let precedence = match (&token, operator) {
+                (Token::Or, _) => 1,
+                (Token::And, _) => 2,
+                (_, Some(operator)) => operator.precedence(),
+                _ => break,
+            };
+
+            if precedence < min_precedence {
+                break;
             }
-        } else {
-            single_expr
+
+            self.cursor.eat();
+
+            // '^' is the only right associative operator
+            let next_precedence = if operator == Some(Operator::Pow) {
+                precedence
+            } else {
+                precedence + 1
+            };
+
+            let r = self
+                .parse_binary_expression(next_precedence)?
+                .ok_or_else(|| format!("Expected expression after {token:?}"))?;
+
+            l = match operator {
+                Some(operator) => Expression::Operation(operator, Box::new(l), Box::new(r)),
+                None if token == Token::And => Expression::And(Box::new(l), Box::new(r)),
+                None => Expression::Or(Box::new(l), Box::new(r)),
+            };
         }
-    }
 
-    fn parse_parameter(&mut self) -> Option<Expression> {
-        let ident = self
-            .cursor
-            .eat_iff(|token| matches!(token, Token::Identifier(_)));
+        Ok(Some(l))
+    }
 
-        let ident = ident.as_ref()?;
-        let ident = match ident {
-            Token::Identifier(ident) => Expression::Identifier(ident.to_string()),
-            _ => panic!(),
+    fn parse_enum(&mut self) -> ParseResult<Statement> {
+        let ident = match self.cursor.eat() {
+            Some(Token::Identifier(ident)) => Expression::Identifier(ident),
+            token => return Err(format!("Expected enum identifier, got {token:?}")),
         };
 
-        let expected_type = if self
-            .cursor
+        let mut variants = vec![];
+
+        self.expect(|token| matches!(token, Token::LScope), "Expected '{'")?;
+
+        while let Some(Token::Identifier(variant)) = self
+            .cursor
+            .eat_iff(|token| matches!(token, Token::Identifier(_)))
+        {
+            let fields = if matches!(self.cursor.peek(None), Some(Token::LParen)) {
+                self.parse_parameters()?
+            } else {
+                vec![]
+            };
+
+            variants.push(Expression::EnumVariant {
+                ident: Box::new(Expression::Identifier(variant)),
+                fields,
+            });
+
+            self.cursor.eat_iff(|token| matches!(token, Token::Comma));
+        }
+
+        self.expect(|token| matches!(token, Token::RScope), "Expected '}'")?;
+
+        Ok(Statement::Enum { ident, variants })
+    }
+
+    fn parse_pattern(&mut self) -> ParseResult<Pattern> {
+        let pattern = self.parse_single_pattern()?;
+
+        if matches!(self.cursor.peek(None), Some(Token::Pipe)) {
+            let mut alternatives = vec![pattern];
+
+            while self
+                .cursor
+                .eat_iff(|token| matches!(token, Token::Pipe))
+                .is_some()
+            {
+                alternatives.push(self.parse_single_pattern()?);
+            }
+
+            Ok(Pattern::Or(alternatives))
+        } else {
+            Ok(pattern)
+        }
+    }
+
+    fn parse_pattern_literal(&mut self) -> ParseResult<Expression> {
+        Ok(match self.cursor.eat() {
+            Some(Token::Minus) => match self.cursor.eat() {
+                Some(Token::Literal(Literal::Number(number))) => Expression::Number(-number),
+                token => return Err(format!("Expected number after '-', got {token:?}")),
+            },
+            Some(Token::Literal(literal)) => match literal {
+                Literal::Char(char) => Expression::Char(char),
+                Literal::Identifier(ident) => Expression::Identifier(ident),
+                Literal::Number(number) => Expression::Number(number),
+                Literal::String(string) => Expression::String(string),
+                Literal::Bool(bool) => Expression::Bool(bool),
+            },
+            token => return Err(format!("Expected literal pattern, got {token:?}")),
+        })
+    }
+
+    fn parse_single_pattern(&mut self) -> ParseResult<Pattern> {
+        Ok(match self.cursor.peek(None) {
+            Some(Token::Literal(_) | Token::Minus) => {
+                let start = self.parse_pattern_literal()?;
+
+                if self
+                    .cursor
+                    .eat_iff(|token| matches!(token, Token::DotDot))
+                    .is_some()
+                {
+                    let inclusive = self
+                        .cursor
+                        .eat_iff(|token| matches!(token, Token::Equal))
+                        .is_some();
+
+                    Pattern::Range {
+                        start,
+                        end: self.parse_pattern_literal()?,
+                        inclusive,
+                    }
+                } else {
+                    Pattern::Literal(start)
+                }
+            }
+            Some(Token::LScope) => {
+                self.cursor.eat();
+
+                let mut fields = vec![];
+
+                while let Some(Token::Identifier(field)) = self
+                    .cursor
+                    .eat_iff(|token| matches!(token, Token::Identifier(_)))
+                {
+                    let pattern = if self
+                        .cursor
+                        .eat_iff(|token| matches!(token, Token::Colon))
+                        .is_some()
+                    {
+                        Some(self.parse_pattern()?)
+                    } else {
+                        None
+                    };
+
+                    fields.push((field, pattern));
+
+                    if self
+                        .cursor
+                        .eat_iff(|token| matches!(token, Token::Comma))
+                        .is_none()
+                    {
+                        break;
+                    }
+                }
+
+                self.expect(
+                    |token| matches!(token, Token::RScope),
+                    "Expected '}' after table pattern",
+                )?;
+
+                Pattern::Table(fields)
+            }
+            Some(Token::LBracket) => {
+                self.cursor.eat();
+
+                let mut items = vec![];
+                let mut rest = None;
+
+                while self
+                    .cursor
+                    .peek_iff(None, |token| !matches!(token, Token::RBracket))
+                    .is_some()
+                {
+                    if self
+                        .cursor
+                        .eat_iff(|token| matches!(token, Token::DotDot))
+                        .is_some()
+                    {
+                        rest = Some(
+                            match self
+                                .cursor
+                                .eat_iff(|token| matches!(token, Token::Identifier(_)))
+                            {
+                                Some(Token::Identifier(ident)) => Some(ident),
+                                _ => None,
+                            },
+                        );
+
+                        break;
+                    }
+
+                    items.push(self.parse_pattern()?);
+
+                    if self
+                        .cursor
+                        .eat_iff(|token| matches!(token, Token::Comma))
+                        .is_none()
+                    {
+                        break;
+                    }
+                }
+
+                self.expect(
+                    |token| matches!(token, Token::RBracket),
+                    "Expected ']' after array pattern",
+                )?;
+
+                Pattern::Array { items, rest }
+            }
+            _ => self.parse_variant_pattern()?,
+        })
+    }
+
+    fn parse_variant_pattern(&mut self) -> ParseResult<Pattern> {
+        Ok(match self.cursor.eat() {
+            Some(Token::Identifier(ident)) if ident == "_" => Pattern::Wildcard,
+            Some(Token::Identifier(ident)) if ident == "nil" => {
+                Pattern::Literal(Expression::Identifier(ident))
+            }
+            Some(Token::Identifier(ident)) => {
+                let (enum_ident, ident) = if self
+                    .cursor
+                    .eat_iff(|token| matches!(token, Token::Dot))
+                    .is_some()
+                {
+                    match self.cursor.eat() {
+                        Some(Token::Identifier(variant)) => (Some(ident), variant),
+                        token => return Err(format!("Expected variant identifier, got {token:?}")),
+                    }
+                } else {
+                    (None, ident)
+                };
+
+                if self
+                    .cursor
+                    .eat_iff(|token| matches!(token, Token::LParen))
+                    .is_some()
+                {
+                    let mut fields = vec![];
+
+                    while self
+                        .cursor
+                        .peek_iff(None, |token| !matches!(token, Token::RParen))
+                        .is_some()
+                    {
+                        fields.push(self.parse_pattern()?);
+
+                        if self
+                            .cursor
+                            .eat_iff(|token| matches!(token, Token::Comma))
+                            .is_none()
+                        {
+                            break;
+                        }
+                    }
+
+                    self.expect(|token| matches!(token, Token::RParen), "Expected ')'")?;
+
+                    Pattern::Variant {
+                        enum_ident,
+                        ident,
+                        fields,
+                    }
+                } else if enum_ident.is_some() {
+                    Pattern::Variant {
+                        enum_ident,
+                        ident,
+                        fields: vec![],
+                    }
+                } else {
+                    Pattern::Identifier(ident)
+                }
+            }
+            token => return Err(format!("Expected pattern, got {token:?}")),
+        })
+    }
+
+    /// Parses `match value { pattern => body, ... }`. Arms of a match used as an
+    /// expression return their value, arms of a match statement are statements.
+    fn parse_match(&mut self, expression: bool) -> ParseResult<(Expression, Vec<MatchArm>)> {
+        let value = self
+            .parse_expression()?
+            .ok_or("Expected value to match on")?;
+        let mut arms = vec![];
+
+        self.expect(|token| matches!(token, Token::LScope), "Expected '{'")?;
+
+        while self
+            .cursor
+            .peek_iff(None, |token| !matches!(token, Token::RScope))
+            .is_some()
+        {
+            let pattern = self.parse_pattern()?;
+
+            let guard = if self
+                .cursor
+                .eat_iff(|token| matches!(token, Token::Identifier(keyword) if keyword == "if"))
+                .is_some()
+            {
+                Some(self.parse_expression()?.ok_or("Expected match guard")?)
+            } else {
+                None
+            };
+
+            self.expect(
+                |token| matches!(token, Token::FatArrow),
+                "Expected '=>' after match pattern",
+            )?;
+
+            let body = if self
+                .cursor
+                .eat_iff(|token| matches!(token, Token::LScope))
+                .is_some()
+            {
+                let scope = self.parse_scope()?;
+
+                self.expect(|token| matches!(token, Token::RScope), "Expected '}'")?;
+
+                scope
+            } else if expression {
+                Statement::Return(self.parse_expression()?.ok_or("Expected match arm value")?)
+            } else {
+                self.parse_statement()?
+                    .ok_or("Expected match arm statement")?
+            };
+
+            arms.push(MatchArm {
+                pattern,
+                guard,
+                body,
+            });
+
+            self.cursor.eat_iff(|token| matches!(token, Token::Comma));
+        }
+
+        self.expect(|token| matches!(token, Token::RScope), "Expected '}'")?;
+
+        Ok((value, arms))
+    }
+
+    fn parse_parameter(&mut self) -> ParseResult<Option<Expression>> {
+        let variadic = self
+            .cursor
+            .eat_iff(|token| matches!(token, Token::Ellipsis))
+            .is_some();
+
+        let ident = self
+            .cursor
+            .eat_iff(|token| matches!(token, Token::Identifier(_)));
+
+        let ident = match ident {
+            Some(Token::Identifier(ident)) => Expression::Identifier(ident),
+            None if variadic => return Err("Expected identifier after '...'".into()),
+            _ => return Ok(None),
+        };
+
+        let expected_type = self
+            .cursor
             .eat_iff(|token| matches!(token, Token::Colon))
+            .map(|_| self.parse_type())
+            .transpose()?;
+
+        let default = if self
+            .cursor
+            .eat_iff(|token| matches!(token, Token::Equal))
             .is_some()
         {
-            self.parse_single_expression()
+            if variadic {
+                return Err("Variadic parameter can't have a default value".into());
+            }
+
+            Some(
+                self.parse_expression()?
+                    .ok_or("Expected default parameter value")?,
+            )
         } else {
             None
         };
 
-        Some(Expression::Parameter {
+        Ok(Some(Expression::Parameter {
             ident: Box::new(ident),
-            expected_type: Box::new(expected_type),
-        })
+            expected_type,
+            default: Box::new(default),
+            variadic,
+        }))
     }
 
-    fn parse_class_body(&mut self) -> Expression {
-        let mut properties = vec![];
-        let mut methods = vec![];
+    fn parse_accessor(&mut self) -> ParseResult<Statement> {
+        let ident = match self.cursor.eat() {
+            Some(Token::Identifier(ident)) => Expression::Identifier(ident),
+            token => return Err(format!("Expected accessor identifier, got {token:?}")),
+        };
 
-        self.cursor
-            .eat_iff(|token| matches!(token, Token::LScope))
-            .unwrap();
+        self.parse_member_function(ident)
+    }
 
-        while self
+    fn parse_operator(&mut self) -> ParseResult<Statement> {
+        let operator = self
+            .cursor
+            .eat()
+            .and_then(|token| Operator::from_token(&token))
+            .ok_or("Expected operator after 'operator'")?;
+
+        if matches!(operator, Operator::Gt | Operator::Ge | Operator::Ne) {
+            return Err(format!(
+                "Operator '{}' can't be overloaded, overload '<', '<=' or '==' instead",
+                operator.symbol()
+            ));
+        }
+
+        self.parse_member_function(Expression::Identifier(operator.symbol().into()))
+    }
+
+    fn parse_member_function(&mut self, ident: Expression) -> ParseResult<Statement> {
+        Ok(Statement::VariableDeclaration {
+            ident,
+            value_type: None,
+            value: self.parse_function()?,
+        })
+    }
+
+    fn parse_observer(&mut self, ident: Expression) -> ParseResult<Statement> {
+        let mut params = vec![];
+
+        if self
             .cursor
-            .eat_iff(|token| !matches!(token, Token::RScope))
+            .eat_iff(|token| matches!(token, Token::LParen))
             .is_some()
         {
-            let property = self.parse_variable();
+            if let Some(param) = self.parse_parameter()? {
+                params.push(param);
+            }
+
+            self.expect(|token| matches!(token, Token::RParen), "Expected ')'")?;
+        }
+
+        self.expect(|token| matches!(token, Token::LScope), "Expected '{'")?;
+
+        let scope = self.parse_scope()?;
+
+        self.expect(|token| matches!(token, Token::RScope), "Expected '}'")?;
+
+        Ok(Statement::VariableDeclaration {
+            ident,
+            value_type: None,
+            value: Expression::Function {
+                generics: vec![],
+                params,
+                return_type: None,
+                stmt: Box::new(scope),
+            },
+        })
+    }
+
+    fn parse_class_body(&mut self) -> ParseResult<Expression> {
+        let mut properties = vec![];
+        let mut methods = vec![];
+        let mut getters = vec![];
+        let mut setters = vec![];
+        let mut observers = vec![];
+        let mut metamethods = vec![];
+
+        self.expect(|token| matches!(token, Token::LScope), "Expected '{'")?;
+
+        while let Some(token) = self.cursor.eat_iff(|token| !matches!(token, Token::RScope)) {
+            match token {
+                Token::Identifier(keyword) if keyword == "get" => {
+                    getters.push(self.parse_accessor()?);
+                    continue;
+                }
+                Token::Identifier(keyword) if keyword == "set" => {
+                    setters.push(self.parse_accessor()?);
+                    continue;
+                }
+                Token::Identifier(keyword) if keyword == "operator" => {
+                    metamethods.push(self.parse_operator()?);
+                    continue;
+                }
+                Token::Identifier(keyword)
+                    if matches!(keyword.as_str(), "tostring" | "call" | "len") =>
+                {
+                    metamethods.push(self.parse_member_function(Expression::Identifier(keyword))?);
+                    continue;
+                }
+                _ => {}
+            }
+
+            let property = self.parse_variable()?;
 
             if let Some(property) = property {
-                if let Statement::VariableDeclaration { ident: _, value } = &property {
+                if let Statement::VariableDeclaration { ident, value, .. } = &property {
                     if matches!(value, Expression::Function { .. }) {
                         methods.push(property);
                     } else {
+                        if matches!(
+                            self.cursor.peek(None),
+                            Some(Token::Identifier(keyword)) if keyword == "didSet"
+                        ) {
+                            self.cursor.eat();
+                            observers.push(self.parse_observer(ident.clone())?);
+                        }
+
                         properties.push(property);
                     }
                 }
@@ -284,179 +1414,436 @@ impl Parser {
 
         self.cursor.eat();
 
-        Expression::ClassBody {
+        Ok(Expression::ClassBody {
             properties,
             methods,
-        }
+            getters,
+            setters,
+            observers,
+            metamethods,
+        })
     }
 
-    fn parse_if_statement(&mut self) -> Statement {
-        let expr = self.parse_expression().unwrap();
+    fn parse_if_statement(&mut self) -> ParseResult<Statement> {
+        let expr = self
+            .parse_expression()?
+            .ok_or("Expected condition after 'if'")?;
 
-        self.cursor
-            .eat_iff(|token| matches!(token, Token::LScope))
-            .unwrap();
+        self.expect(|token| matches!(token, Token::LScope), "Expected '{'")?;
 
-        let body = self.parse_scope();
+        let body = self.parse_scope()?;
 
-        self.cursor
-            .eat_iff(|token| matches!(token, Token::RScope))
-            .unwrap();
+        self.expect(|token| matches!(token, Token::RScope), "Expected '}'")?;
 
-        Statement::If {
+        Ok(Statement::If {
             expr,
             body: Box::new(body),
+        })
+    }
+
+    fn parse_class(&mut self) -> ParseResult<Statement> {
+        let ident = match self.cursor.eat() {
+            Some(Token::Identifier(ident)) => Expression::Identifier(ident),
+            token => return Err(format!("Expected class identifier, got {token:?}")),
+        };
+        let generics = self.parse_generics()?;
+        let mut implements = vec![];
+
+        if self
+            .cursor
+            .eat_iff(|token| matches!(token, Token::Identifier(keyword) if keyword == "implements"))
+            .is_some()
+        {
+            while let Some(Token::Identifier(interface)) = self
+                .cursor
+                .eat_iff(|token| matches!(token, Token::Identifier(_)))
+            {
+                implements.push(Expression::Identifier(interface));
+
+                if self
+                    .cursor
+                    .eat_iff(|token| matches!(token, Token::Comma))
+                    .is_none()
+                {
+                    break;
+                }
+            }
         }
+
+        Ok(Statement::ClassConstructor {
+            ident,
+            generics,
+            implements,
+            body: self.parse_class_body()?,
+        })
     }
 
-    fn parse_class(&mut self) -> Statement {
-        let ident = self.parse_expression();
+    fn parse_interface(&mut self) -> ParseResult<Statement> {
+        let ident = match self.cursor.eat() {
+            Some(Token::Identifier(ident)) => Expression::Identifier(ident),
+            token => return Err(format!("Expected interface identifier, got {token:?}")),
+        };
+
+        let mut properties = vec![];
+        let mut methods = vec![];
+
+        self.expect(|token| matches!(token, Token::LScope), "Expected '{'")?;
 
-        Statement::ClassConstructor {
-            ident: ident.expect("Expected class identifier"),
-            body: self.parse_class_body(),
+        while let Some(Token::Identifier(member)) = self
+            .cursor
+            .eat_iff(|token| matches!(token, Token::Identifier(_)))
+        {
+            let member = Expression::Identifier(member);
+
+            if matches!(self.cursor.peek(None), Some(Token::LParen | Token::Less)) {
+                let generics = self.parse_generics()?;
+                let params = self.parse_parameters()?;
+                let return_type = self.parse_return_type()?;
+
+                methods.push(Statement::VariableDeclaration {
+                    ident: member,
+                    value_type: None,
+                    value: Expression::Function {
+                        generics,
+                        params,
+                        return_type,
+                        stmt: Box::new(Statement::Scope(vec![])),
+                    },
+                });
+            } else {
+                self.expect(
+                    |token| matches!(token, Token::Colon),
+                    "Expected ':' or '(' after interface member",
+                )?;
+
+                properties.push(Expression::Parameter {
+                    ident: Box::new(member),
+                    expected_type: Some(self.parse_type()?),
+                    default: Box::new(None),
+                    variadic: false,
+                });
+            }
+
+            self.cursor.eat_iff(|token| matches!(token, Token::Comma));
         }
+
+        self.expect(|token| matches!(token, Token::RScope), "Expected '}'")?;
+
+        Ok(Statement::Interface {
+            ident,
+            properties,
+            methods,
+        })
     }
 
-    fn parse_variable(&mut self) -> Option<Statement> {
-        let ident = self.parse_expression();
-        let ident = ident.as_ref()?.clone();
+    fn parse_type_alias(&mut self, export: bool) -> ParseResult<Statement> {
+        let ident = match self.cursor.eat() {
+            Some(Token::Identifier(ident)) => ident,
+            token => return Err(format!("Expected type identifier, got {token:?}")),
+        };
+
+        let generics = self.parse_generics()?;
+
+        self.expect(
+            |token| matches!(token, Token::Equal),
+            "Expected '=' after type identifier",
+        )?;
 
-        self.cursor.eat_iff(|token| matches!(token, Token::Equal))?;
+        Ok(Statement::TypeAlias {
+            ident,
+            generics,
+            value: self.parse_type()?,
+            export,
+        })
+    }
 
-        if matches!(self.cursor.peek(None), Some(Token::LParen)) {
-            let mut params: Vec<Expression> = vec![];
-            self.cursor.eat(); // (
+    fn parse_import(&mut self) -> ParseResult<Statement> {
+        self.expect(
+            |token| matches!(token, Token::LScope),
+            "Expected '{' after 'import'",
+        )?;
 
-            while let Some(expr) = self.parse_parameter() {
-                params.push(expr);
+        let mut idents = vec![];
 
-                if matches!(self.cursor.peek(None), Some(Token::Comma)) {
-                    self.cursor.eat();
-                } else {
-                    break;
-                }
+        while let Some(token) = self.cursor.eat_iff(|token| !matches!(token, Token::RScope)) {
+            match token {
+                Token::Identifier(ident) => idents.push(ident),
+                token => return Err(format!("Expected name to import, got {token:?}")),
+            }
+
+            if self
+                .cursor
+                .eat_iff(|token| matches!(token, Token::Comma))
+                .is_none()
+            {
+                break;
             }
+        }
 
-            self.cursor
-                .eat_iff(|token| matches!(token, Token::RParen))
-                .unwrap();
+        self.expect(
+            |token| matches!(token, Token::RScope),
+            "Expected '}' after imported names",
+        )?;
+        self.expect(
+            |token| matches!(token, Token::Identifier(keyword) if keyword == "from"),
+            "Expected 'from' after imported names",
+        )?;
+
+        let path = match self.cursor.eat() {
+            Some(Token::Literal(Literal::String(path))) => path,
+            token => return Err(format!("Expected module path, got {token:?}")),
+        };
 
-            self.cursor
-                .eat_iff(|token| matches!(token, Token::LScope))
-                .unwrap();
+        Ok(Statement::Import { idents, path })
+    }
 
-            let scope = self.parse_scope();
+    fn parse_export(&mut self) -> ParseResult<Statement> {
+        let stmt = self
+            .parse_statement()?
+            .ok_or("Expected declaration after 'export'")?;
 
-            self.cursor
-                .eat_iff(|token| matches!(token, Token::RScope))
-                .unwrap();
+        Ok(match stmt {
+            Statement::VariableDeclaration {
+                ident: Expression::Identifier(_),
+                ..
+            }
+            | Statement::Constant { .. }
+            | Statement::ClassConstructor { .. }
+            | Statement::Enum { .. }
+            | Statement::Interface { .. } => Statement::Export(Box::new(stmt)),
+            stmt => return Err(format!("Only declarations can be exported, got {stmt:?}")),
+        })
+    }
 
-            Some(Statement::VariableDeclaration {
-                ident,
-                value: Expression::Function {
-                    params,
-                    stmt: Box::new(scope),
-                },
-            })
-        } else {
-            let value = self.parse_expression();
-            let value = value.as_ref()?.clone();
+    fn parse_variable(&mut self) -> ParseResult<Option<Statement>> {
+        let (ident, value_type) = match (self.cursor.peek(None), self.cursor.peek(Some(2))) {
+            (Some(Token::Identifier(ident)), Some(Token::Colon)) => {
+                self.cursor.eat();
+                self.cursor.eat();
 
-            Some(Statement::VariableDeclaration { ident, value })
+                (Expression::Identifier(ident), Some(self.parse_type()?))
+            }
+            _ => match self.parse_expression()? {
+                Some(ident) => (ident, None),
+                None => return Ok(None),
+            },
+        };
+
+        if self
+            .cursor
+            .eat_iff(|token| matches!(token, Token::Equal))
+            .is_none()
+        {
+            return Ok(None);
         }
+
+        let function = match self.cursor.peek(None) {
+            Some(Token::Less) => true,
+            Some(Token::LParen) => self.is_function(),
+            _ => false,
+        };
+
+        let value = if function {
+            self.parse_function()?
+        } else {
+            match self.parse_expression()? {
+                Some(value) => value,
+                None => return Ok(None),
+            }
+        };
+
+        Ok(Some(Statement::VariableDeclaration {
+            ident,
+            value_type,
+            value,
+        }))
     }
 
-    fn parse_variable_assignment(&mut self, ident: Option<Expression>) -> Statement {
-        let ident = ident.unwrap_or_else(|| self.parse_expression().unwrap());
+    fn parse_variable_assignment(&mut self, ident: Option<Expression>) -> ParseResult<Statement> {
+        let ident = match ident {
+            Some(ident) => ident,
+            None => self
+                .parse_expression()?
+                .ok_or("Expected assignment target")?,
+        };
 
-        self.cursor
-            .eat_iff(|token| matches!(token, Token::Equal))
-            .unwrap();
+        self.expect(|token| matches!(token, Token::Equal), "Expected '='")?;
 
-        let value = self.parse_expression().unwrap();
+        let value = self.parse_expression()?.ok_or("Expected value after '='")?;
 
-        Statement::VariableAssignment { ident, value }
+        Ok(Statement::VariableAssignment { ident, value })
     }
 
-    fn parse_fn_call(&mut self, ident: Option<Expression>) -> Statement {
-        let ident = ident.unwrap_or_else(|| self.parse_expression().unwrap());
+    fn parse_fn_call(&mut self, ident: Option<Expression>) -> ParseResult<Statement> {
+        let ident = match ident {
+            Some(ident) => ident,
+            None => self.parse_expression()?.ok_or("Expected expression")?,
+        };
 
-        Statement::VariableDeclaration {
+        Ok(Statement::VariableDeclaration {
             ident: Expression::Identifier(String::from("_")),
+            value_type: None,
             value: ident,
-        }
+        })
     }
 
-    pub fn parse_statement(&mut self) -> Option<Statement> {
+    pub fn parse_statement(&mut self) -> ParseResult<Option<Statement>> {
         if let Some(token) = self.cursor.peek(None) {
-            match token.clone() {
+            Ok(match token.clone() {
                 Token::Identifier(identifier) => match identifier.as_str() {
                     "let" => {
                         self.cursor.eat();
-                        Some(self.parse_variable().unwrap())
+                        Some(
+                            self.parse_variable()?
+                                .ok_or("Expected 'let NAME = value'")?,
+                        )
+                    }
+                    "const" => {
+                        self.cursor.eat();
+
+                        match self.parse_variable()? {
+                            Some(Statement::VariableDeclaration {
+                                ident: ident @ Expression::Identifier(_),
+                                value_type,
+                                value,
+                            }) => Some(Statement::Constant {
+                                ident,
+                                value_type,
+                                value,
+                            }),
+                            _ => return Err("Expected 'const NAME = value'".into()),
+                        }
                     }
                     "class" => {
                         self.cursor.eat();
-                        Some(self.parse_class())
+                        Some(self.parse_class()?)
+                    }
+                    "interface" => {
+                        self.cursor.eat();
+                        Some(self.parse_interface()?)
+                    }
+                    "enum" => {
+                        self.cursor.eat();
+                        Some(self.parse_enum()?)
+                    }
+                    "type" if matches!(self.cursor.peek(Some(2)), Some(Token::Identifier(_))) => {
+                        self.cursor.eat();
+                        Some(self.parse_type_alias(false)?)
+                    }
+                    "export"
+                        if matches!(
+                            self.cursor.peek(Some(2)),
+                            Some(Token::Identifier(keyword)) if keyword == "type"
+                        ) =>
+                    {
+                        self.cursor.eat();
+                        self.cursor.eat();
+                        Some(self.parse_type_alias(true)?)
+                    }
+                    "export" => {
+                        self.cursor.eat();
+                        Some(self.parse_export()?)
+                    }
+                    "import" => {
+                        self.cursor.eat();
+                        Some(self.parse_import()?)
+                    }
+                    "match" => {
+                        self.cursor.eat();
+
+                        let (value, arms) = self.parse_match(false)?;
+
+                        Some(Statement::Match { value, arms })
                     }
                     "if" => {
                         self.cursor.eat();
-                        Some(self.parse_if_statement())
+                        Some(self.parse_if_statement()?)
                     }
                     "return" => {
                         self.cursor.eat();
                         Some(Statement::Return(
-                            self.parse_expression()
+                            self.parse_expression()?
                                 .unwrap_or(Expression::Identifier("nil".into())),
                         ))
                     }
                     _ => {
-                        let index = self.parse_expression();
+                        let index = self.parse_expression()?;
 
                         if matches!(self.cursor.peek(None), Some(Token::Equal)) {
-                            Some(self.parse_variable_assignment(index))
+                            Some(self.parse_variable_assignment(index)?)
                         } else {
-                            Some(self.parse_fn_call(index))
+                            Some(self.parse_fn_call(index)?)
                         }
                     }
                 },
                 Token::LScope => {
                     self.cursor.eat();
 
-                    let scope = self.parse_scope();
+                    let scope = self.parse_scope()?;
 
-                    self.cursor
-                        .eat_iff(|token| matches!(token, Token::RScope))
-                        .unwrap();
+                    self.expect(|token| matches!(token, Token::RScope), "Expected '}'")?;
 
                     Some(scope)
                 }
                 Token::RScope => None,
+                Token::At => {
+                    self.cursor.eat();
+
+                    match self.cursor.eat() {
+                        Some(Token::Identifier(name)) => Some(Statement::Annotation(name)),
+                        token => {
+                            return Err(format!(
+                                "Expected annotation name after '@', got {token:?}"
+                            ))
+                        }
+                    }
+                }
                 Token::Luau(code) => {
                     self.cursor.eat();
 
                     Some(Statement::Luau(code))
                 }
-                _ => todo!("{token:?}"),
-            }
+                _ => return Err(format!("Unexpected {token:?}")),
+            })
         } else {
-            None
+            Ok(None)
         }
     }
 
-    pub fn parse_scope(&mut self) -> Statement {
-        let mut stack: Vec<Statement> = Vec::new();
-
-        while let Some(expr) = self.parse_statement() {
+    fn parse_statements(&mut self, stack: &mut Vec<Statement>) -> ParseResult<()> {
+        while let Some(expr) = self.parse_statement()? {
             stack.push(expr);
         }
 
-        Statement::Scope(stack)
+        Ok(())
     }
 
-    pub fn load(&mut self) -> Statement {
-        self.parse_scope()
+    pub fn parse_scope(&mut self) -> ParseResult<Statement> {
+        let mut stack = vec![];
+
+        self.parse_statements(&mut stack)?;
+
+        Ok(Statement::Scope(stack))
+    }
+
+    pub fn load(&mut self) -> Result<Statement, SyntaxError> {
+        let mut stack = vec![];
+
+        let parsed = self.parse_statements(&mut stack).and_then(|()| {
+            // Statements stop at a '}', which has nothing to close up here
+            match self.cursor.peek(None) {
+                Some(token) => Err(format!("Unexpected {token:?}")),
+                None => Ok(()),
+            }
+        });
+
+        match parsed {
+            Ok(()) => Ok(Statement::Scope(stack)),
+            Err(message) => Err(SyntaxError {
+                message,
+                parsed: Box::new(Statement::Scope(stack)),
+            }),
+        }
     }
 
     pub fn new(tokens: &[Token]) -> Self {
@@ -465,3 +1852,52 @@ impl Parser {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::modules::imports;
+
+    #[test]
+    fn syntax_errors_keep_the_statements_before_them() {
+        let error = parse("import { x } from \"./b\"\nlet y = (\n").unwrap_err();
+
+        assert_eq!(error.message, "Expected expression after '('");
+        assert_eq!(imports(&error.parsed), ["./b"]);
+    }
+
+    #[test]
+    fn lexer_errors_keep_the_statements_before_them() {
+        let error = parse("import { x } from \"./b\"\nlet y = x and 1\n").unwrap_err();
+
+        assert_eq!(error.message, "Did you mean to use '&' instead of 'and'?");
+        assert_eq!(imports(&error.parsed), ["./b"]);
+    }
+
+    #[test]
+    fn unexpected_tokens_are_errors() {
+        assert!(parse("let x = 1\n}\nlet y = 2").is_err());
+        assert!(parse("let x = 1 ]").is_err());
+        assert!(parse("if { }").is_err());
+        assert!(parse("=> 1").is_err());
+        assert!(parse("let x = (1, 2").is_err());
+    }
+
+    #[test]
+    fn only_overloadable_operators_are_accepted() {
+        let error =
+            parse("class V {\n    operator &(other) {\n        return 1\n    }\n}").unwrap_err();
+
+        assert_eq!(error.message, "Expected operator after 'operator'");
+    }
+
+    #[test]
+    fn variadic_parameters_come_last() {
+        let error = parse("let f = (...rest, last) {}").unwrap_err();
+
+        assert_eq!(
+            error.message,
+            "Expected ')' after parameters, a variadic parameter must come last"
+        );
+    }
+}
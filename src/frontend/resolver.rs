@@ -0,0 +1,578 @@
+use std::collections::HashMap;
+
+use crate::{
+    frontend::parser::{Expression, MatchArm, Pattern, Statement},
+    util::diagnostic::{Diagnostic, Reporter},
+};
+
+/// The lints whose levels can be configured. `script-api` is reported by
+/// [`crate::frontend::scripts`], the others by the resolver.
+pub const LINTS: [&str; 4] = [
+    "undefined",
+    "shadowing",
+    "undeclared-assignment",
+    "script-api",
+];
+
+/// Luau globals that can be used without being declared.
+pub const LUAU_GLOBALS: [&str; 39] = [
+    "_G",
+    "_VERSION",
+    "assert",
+    "bit32",
+    "buffer",
+    "collectgarbage",
+    "coroutine",
+    "debug",
+    "error",
+    "gcinfo",
+    "getfenv",
+    "getmetatable",
+    "ipairs",
+    "loadstring",
+    "math",
+    "newproxy",
+    "next",
+    "os",
+    "pairs",
+    "pcall",
+    "print",
+    "rawequal",
+    "rawget",
+    "rawlen",
+    "rawset",
+    "require",
+    "select",
+    "setfenv",
+    "setmetatable",
+    "string",
+    "table",
+    "tonumber",
+    "tostring",
+    "type",
+    "typeof",
+    "unpack",
+    "utf8",
+    "vector",
+    "xpcall",
+];
+
+/// Globals that only exist in Roblox.
+pub const ROBLOX_GLOBALS: [&str; 54] = [
+    "Axes",
+    "BrickColor",
+    "CFrame",
+    "CatalogSearchParams",
+    "Color3",
+    "ColorSequence",
+    "ColorSequenceKeypoint",
+    "Content",
+    "DateTime",
+    "DockWidgetPluginGuiInfo",
+    "Enum",
+    "Faces",
+    "FloatCurveKey",
+    "Font",
+    "Instance",
+    "NumberRange",
+    "NumberSequence",
+    "NumberSequenceKeypoint",
+    "OverlapParams",
+    "Path2DControlPoint",
+    "PathWaypoint",
+    "PhysicalProperties",
+    "Random",
+    "Ray",
+    "RaycastParams",
+    "Rect",
+    "Region3",
+    "Region3int16",
+    "RotationCurveKey",
+    "SharedTable",
+    "TweenInfo",
+    "UDim",
+    "UDim2",
+    "UserSettings",
+    "Vector2",
+    "Vector2int16",
+    "Vector3",
+    "Vector3int16",
+    "delay",
+    "elapsedTime",
+    "game",
+    "plugin",
+    "script",
+    "settings",
+    "shared",
+    "spawn",
+    "stats",
+    "task",
+    "tick",
+    "time",
+    "version",
+    "wait",
+    "warn",
+    "workspace",
+];
+
+/// The name a class or its member is declared with.
+fn member_name(ident: &Expression) -> String {
+    match ident {
+        Expression::Identifier(ident) => ident.clone(),
+        _ => "?".into(),
+    }
+}
+
+/// Builds the scopes of a program and reports names that are used without
+/// being declared, declarations that shadow another variable and
+/// assignments to variables that were never declared or are constants.
+pub struct Resolver {
+    pub reporter: Reporter,
+    /// Names that are always in scope, the Luau and Roblox globals by default
+    pub globals: Vec<String>,
+    /// The names declared in each scope, and whether they're constants
+    scopes: Vec<HashMap<String, bool>>,
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self {
+            reporter: Reporter::default(),
+            globals: LUAU_GLOBALS
+                .iter()
+                .chain(&ROBLOX_GLOBALS)
+                .map(|global| global.to_string())
+                .collect(),
+            scopes: vec![],
+        }
+    }
+}
+
+impl Resolver {
+    fn is_declared(&self, ident: &str) -> bool {
+        ident == "nil"
+            || self.scopes.iter().any(|scope| scope.contains_key(ident))
+            || self.globals.iter().any(|global| global == ident)
+    }
+
+    fn is_constant(&self, ident: &str) -> bool {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(ident))
+            .is_some_and(|constant| *constant)
+    }
+
+    fn declare(&mut self, ident: &str, constant: bool) {
+        if ident != "_" && self.scopes.iter().any(|scope| scope.contains_key(ident)) {
+            self.reporter.report(
+                Diagnostic::warning(format!("'{ident}' shadows an earlier declaration"))
+                    .with_lint("shadowing"),
+            );
+        }
+
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(ident.into(), constant);
+    }
+
+    /// Declares the locals of a Luau block, so code after it can use them.
+    fn declare_luau(&mut self, code: &str) {
+        for line in code.lines() {
+            let Some(names) = line.trim_start().strip_prefix("local ") else {
+                continue;
+            };
+
+            let names = names.trim_start();
+            let names = names.strip_prefix("function ").unwrap_or(names);
+            let names = names.split('=').next().unwrap();
+
+            for name in names.split(',') {
+                let name = name.split(':').next().unwrap();
+                let name = name
+                    .trim()
+                    .split(|char: char| !(char.is_alphanumeric() || char == '_'))
+                    .next()
+                    .unwrap();
+
+                if !name.is_empty() {
+                    self.scopes.last_mut().unwrap().insert(name.into(), false);
+                }
+            }
+        }
+    }
+
+    fn resolve_expression(&mut self, expr: &Expression) {
+        match expr {
+            Expression::Identifier(ident) => {
+                if !self.is_declared(ident) {
+                    self.reporter.report(
+                        Diagnostic::error(format!("'{ident}' is not defined"))
+                            .with_lint("undefined"),
+                    );
+                }
+            }
+            Expression::Indexing(l, r) => {
+                self.resolve_expression(l);
+                self.resolve_member(r);
+            }
+            Expression::FunctionCall { ident, args } | Expression::MethodCall { ident, args } => {
+                self.resolve_expression(ident);
+
+                for arg in args {
+                    self.resolve_expression(arg);
+                }
+            }
+            Expression::NamedArgument { value, .. } => self.resolve_expression(value),
+            Expression::Function { params, stmt, .. } => self.resolve_function(params, stmt),
+            Expression::Match { value, arms } => {
+                self.resolve_expression(value);
+                self.resolve_arms(arms);
+            }
+            Expression::And(l, r) | Expression::Or(l, r) | Expression::Operation(_, l, r) => {
+                self.resolve_expression(l);
+                self.resolve_expression(r);
+            }
+            Expression::Negate(expr) => self.resolve_expression(expr),
+            Expression::Parameter { .. }
+            | Expression::ClassBody { .. }
+            | Expression::EnumVariant { .. }
+            | Expression::String(_)
+            | Expression::Bool(_)
+            | Expression::Char(_)
+            | Expression::Number(_) => {}
+        }
+    }
+
+    /// Resolves the right side of `a.b`, where identifiers are member names.
+    fn resolve_member(&mut self, expr: &Expression) {
+        match expr {
+            Expression::Identifier(_) => {}
+            Expression::Indexing(l, r) => {
+                self.resolve_member(l);
+                self.resolve_member(r);
+            }
+            Expression::FunctionCall { ident, args } | Expression::MethodCall { ident, args } => {
+                self.resolve_member(ident);
+
+                for arg in args {
+                    self.resolve_expression(arg);
+                }
+            }
+            expr => self.resolve_expression(expr),
+        }
+    }
+
+    fn resolve_function(&mut self, params: &[Expression], stmt: &Statement) {
+        self.scopes.push(HashMap::new());
+
+        for param in params {
+            if let Expression::Parameter { ident, default, .. } = param {
+                if let Some(default) = default.as_ref() {
+                    self.resolve_expression(default);
+                }
+
+                if let Expression::Identifier(ident) = ident.as_ref() {
+                    self.declare(ident, false);
+                }
+            }
+        }
+
+        self.resolve_statement(stmt);
+        self.scopes.pop();
+    }
+
+    fn resolve_arms(&mut self, arms: &[MatchArm]) {
+        for arm in arms {
+            self.scopes.push(HashMap::new());
+            self.bind_pattern(&arm.pattern);
+
+            if let Some(guard) = &arm.guard {
+                self.resolve_expression(guard);
+            }
+
+            self.resolve_statement(&arm.body);
+            self.scopes.pop();
+        }
+    }
+
+    /// Declares the names a pattern binds. Bare names can also be unit enum
+    /// variants, so they never warn about shadowing.
+    fn bind_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Identifier(ident) => {
+                self.scopes.last_mut().unwrap().insert(ident.clone(), false);
+            }
+            Pattern::Variant { fields, .. } => {
+                for field in fields {
+                    self.bind_pattern(field);
+                }
+            }
+            Pattern::Literal(expr) => self.resolve_expression(expr),
+            Pattern::Range { start, end, .. } => {
+                self.resolve_expression(start);
+                self.resolve_expression(end);
+            }
+            Pattern::Table(fields) => {
+                for (field, pattern) in fields {
+                    match pattern {
+                        Some(pattern) => self.bind_pattern(pattern),
+                        None => self.declare(field, false),
+                    }
+                }
+            }
+            Pattern::Array { items, rest } => {
+                for item in items {
+                    self.bind_pattern(item);
+                }
+
+                if let Some(Some(rest)) = rest {
+                    self.declare(rest, false);
+                }
+            }
+            Pattern::Or(patterns) => {
+                for pattern in patterns {
+                    self.bind_pattern(pattern);
+                }
+            }
+            Pattern::Wildcard => {}
+        }
+    }
+
+    /// Resolves the value of a declaration, with its name as the context of
+    /// the diagnostics in it.
+    fn resolve_value(&mut self, name: &str, value: &Expression) {
+        // Expression statements are declarations of '_'
+        if name == "_" {
+            return self.resolve_expression(value);
+        }
+
+        self.reporter.enter(name);
+        self.resolve_expression(value);
+        self.reporter.exit();
+    }
+
+    fn resolve_class(&mut self, class: &str, body: &Expression) {
+        let Expression::ClassBody {
+            properties,
+            methods,
+            getters,
+            setters,
+            observers,
+            metamethods,
+        } = body
+        else {
+            return;
+        };
+
+        self.scopes.push(HashMap::from([("self".into(), false)]));
+
+        // Fields are accessed through `self`, so only their values are resolved
+        for property in properties {
+            if let Statement::VariableDeclaration { ident, value, .. } = property {
+                self.resolve_value(&format!("{class}.{}", member_name(ident)), value);
+            }
+        }
+
+        for member in methods
+            .iter()
+            .chain(getters)
+            .chain(setters)
+            .chain(observers)
+            .chain(metamethods)
+        {
+            if let Statement::VariableDeclaration { ident, value, .. } = member {
+                self.resolve_value(&format!("{class}.{}", member_name(ident)), value);
+            }
+        }
+
+        self.scopes.pop();
+    }
+
+    fn resolve_statement(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::VariableDeclaration {
+                ident: Expression::Identifier(ident),
+                value,
+                ..
+            } => {
+                // Functions can call themselves
+                if matches!(value, Expression::Function { .. }) {
+                    self.declare(ident, false);
+                    self.resolve_value(ident, value);
+                } else {
+                    self.resolve_value(ident, value);
+                    self.declare(ident, false);
+                }
+            }
+            Statement::VariableAssignment {
+                ident: Expression::Identifier(ident),
+                value,
+            } => {
+                self.resolve_expression(value);
+
+                if self.is_constant(ident) {
+                    self.reporter.report(Diagnostic::error(format!(
+                        "can't assign to '{ident}' because it's a constant"
+                    )));
+                } else if !self.is_declared(ident) {
+                    self.reporter.report(
+                        Diagnostic::warning(format!(
+                            "assignment to undeclared variable '{ident}', did you mean 'let {ident}'?"
+                        ))
+                        .with_lint("undeclared-assignment"),
+                    );
+                }
+            }
+            Statement::Constant {
+                ident: Expression::Identifier(ident),
+                value,
+                ..
+            } => {
+                self.resolve_value(ident, value);
+                self.declare(ident, true);
+            }
+            Statement::VariableDeclaration { ident, value, .. }
+            | Statement::Constant { ident, value, .. }
+            | Statement::VariableAssignment { ident, value } => {
+                self.resolve_expression(value);
+                self.resolve_expression(ident);
+            }
+            Statement::ClassConstructor { ident, body, .. } => {
+                let class = member_name(ident);
+
+                if let Expression::Identifier(ident) = ident {
+                    self.declare(ident, false);
+                }
+
+                self.resolve_class(&class, body);
+            }
+            Statement::Enum {
+                ident: Expression::Identifier(ident),
+                ..
+            } => self.declare(ident, false),
+            Statement::Match { value, arms } => {
+                self.resolve_expression(value);
+                self.resolve_arms(arms);
+            }
+            Statement::Return(expr) => self.resolve_expression(expr),
+            Statement::If { expr, body } => {
+                self.resolve_expression(expr);
+                self.resolve_statement(body);
+            }
+            Statement::Scope(statements) => {
+                self.scopes.push(HashMap::new());
+
+                for stmt in statements {
+                    self.resolve_statement(stmt);
+                }
+
+                self.scopes.pop();
+            }
+            Statement::Luau(code) => self.declare_luau(code),
+            Statement::Import { idents, .. } => {
+                for ident in idents {
+                    self.declare(ident, false);
+                }
+            }
+            Statement::Export(stmt) => self.resolve_statement(stmt),
+            Statement::Enum { .. }
+            | Statement::Interface { .. }
+            | Statement::TypeAlias { .. }
+            | Statement::Annotation(_) => {}
+        }
+    }
+
+    pub fn run(&mut self, root_stmt: &Statement) {
+        let Statement::Scope(statements) = root_stmt else {
+            panic!("Root stmt must be a scope");
+        };
+
+        self.scopes.push(HashMap::new());
+
+        for stmt in statements {
+            self.resolve_statement(stmt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::parser;
+
+    fn diagnostics(src: &str) -> Vec<String> {
+        let mut resolver = Resolver::default();
+
+        resolver.run(&parser::parse(src).unwrap());
+        resolver
+            .reporter
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn reports_undefined_shadowed_and_undeclared_names() {
+        let src = "let speed = 10
+spede = 20
+let f = (speed) { return speed }
+class Player {
+    let update = () {
+        print(nope)
+    }
+}
+";
+
+        assert_eq!(
+            diagnostics(src),
+            [
+                "warning: assignment to undeclared variable 'spede', did you mean 'let spede'?",
+                "warning: in 'f': 'speed' shadows an earlier declaration",
+                "error: in 'Player.update': 'nope' is not defined",
+            ]
+        );
+    }
+
+    #[test]
+    fn declares_the_locals_of_luau_blocks() {
+        let src = r"\\
+local a, b: number = 1, 2
+local function c() end
+\\
+print(a, b, c)
+";
+
+        assert!(diagnostics(src).is_empty(), "{:#?}", diagnostics(src));
+    }
+
+    #[test]
+    fn globals_can_be_added() {
+        let mut resolver = Resolver::default();
+
+        resolver.globals.push("MyGlobal".into());
+        resolver.run(&parser::parse("print(MyGlobal)").unwrap());
+
+        assert!(resolver.reporter.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn constants_cant_be_reassigned() {
+        let src = "const MAX = 50
+MAX = 10
+let f = () {
+    MAX = 1
+}
+";
+
+        assert_eq!(
+            diagnostics(src),
+            [
+                "error: can't assign to 'MAX' because it's a constant",
+                "error: in 'f': can't assign to 'MAX' because it's a constant",
+            ]
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use std::{collections::BTreeSet, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    frontend::{
+        lexer::{Literal, Token},
+        parser::Statement,
+    },
+    util::diagnostic::Diagnostic,
+};
+
+/// APIs that only work in client scripts.
+pub const CLIENT_APIS: [&str; 14] = [
+    "LocalPlayer",
+    "GetMouse",
+    "CurrentCamera",
+    "UserInputService",
+    "ContextActionService",
+    "GuiService",
+    "HapticService",
+    "VRService",
+    "StarterGui",
+    "FireServer",
+    "InvokeServer",
+    "OnClientEvent",
+    "OnClientInvoke",
+    "BindToRenderStep",
+];
+
+/// APIs that only work in server scripts.
+pub const SERVER_APIS: [&str; 11] = [
+    "ServerStorage",
+    "ServerScriptService",
+    "DataStoreService",
+    "MessagingService",
+    "MemoryStoreService",
+    "FireClient",
+    "FireAllClients",
+    "InvokeClient",
+    "OnServerEvent",
+    "OnServerInvoke",
+    "SetNetworkOwner",
+];
+
+/// Whether a file is a `Script`, a `LocalScript` or a `ModuleScript`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptKind {
+    Server,
+    Client,
+    Module,
+}
+
+impl ScriptKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            ScriptKind::Server => "server",
+            ScriptKind::Client => "client",
+            ScriptKind::Module => "module",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "server" => Some(ScriptKind::Server),
+            "client" => Some(ScriptKind::Client),
+            "module" => Some(ScriptKind::Module),
+            _ => None,
+        }
+    }
+
+    /// The kind a file name says, `main.server.cop` is a server script.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let stem = Path::new(path.file_stem()?);
+
+        Self::from_name(stem.extension()?.to_str()?)
+    }
+}
+
+/// The kind of script a file is, from its annotation or otherwise its name.
+pub fn script_kind(
+    root_stmt: &Statement,
+    path: Option<&Path>,
+) -> (Option<ScriptKind>, Vec<Diagnostic>) {
+    let Statement::Scope(statements) = root_stmt else {
+        panic!("Root stmt must be a scope");
+    };
+
+    let named = path.and_then(ScriptKind::from_path);
+    let mut kind = None;
+    let mut diagnostics = vec![];
+
+    for (index, stmt) in statements.iter().enumerate() {
+        let Statement::Annotation(name) = stmt else {
+            continue;
+        };
+
+        // Type aliases don't run, so they can come before the annotation
+        let top = statements[..index]
+            .iter()
+            .all(|stmt| matches!(stmt, Statement::Annotation(_) | Statement::TypeAlias { .. }));
+
+        let Some(annotated) = ScriptKind::from_name(name) else {
+            diagnostics.push(Diagnostic::error(format!(
+                "unknown annotation '@{name}', expected '@server', '@client' or '@module'"
+            )));
+            continue;
+        };
+
+        if !top {
+            diagnostics.push(Diagnostic::error(format!(
+                "'@{name}' has to come before any code"
+            )));
+        } else if kind.is_some_and(|kind| kind != annotated) {
+            diagnostics.push(Diagnostic::error(
+                "a file can only be annotated with one script kind",
+            ));
+        } else if named.is_some_and(|named| named != annotated) {
+            diagnostics.push(Diagnostic::error(format!(
+                "'@{name}' doesn't match the file name, which says it's a {} script",
+                named.unwrap().name()
+            )));
+        }
+
+        kind.get_or_insert(annotated);
+    }
+
+    (kind.or(named), diagnostics)
+}
+
+/// Warns about APIs that only work on the other side of the client-server
+/// boundary, like `LocalPlayer` in a server script.
+pub fn check_apis(
+    tokens: &[Token],
+    kind: ScriptKind,
+    client_apis: &[String],
+    server_apis: &[String],
+) -> Vec<Diagnostic> {
+    let (apis, side) = match kind {
+        ScriptKind::Server => (client_apis, ScriptKind::Client),
+        ScriptKind::Client => (server_apis, ScriptKind::Server),
+        ScriptKind::Module => return vec![],
+    };
+
+    let mut used = BTreeSet::new();
+
+    for token in tokens {
+        match token {
+            Token::Identifier(name) | Token::Literal(Literal::String(name)) => {
+                used.insert(name.as_str());
+            }
+            // Luau blocks aren't parsed, so every word in them is looked at
+            Token::Luau(code) => {
+                used.extend(code.split(|char: char| !char.is_alphanumeric() && char != '_'));
+            }
+            _ => {}
+        }
+    }
+
+    apis.iter()
+        .filter(|api| used.contains(api.as_str()))
+        .map(|api| {
+            Diagnostic::warning(format!(
+                "'{api}' only works in {} scripts, but this is a {} script",
+                side.name(),
+                kind.name()
+            ))
+            .with_lint("script-api")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{backend, frontend::parser};
+
+    fn kind(src: &str, path: &str) -> (Option<ScriptKind>, Vec<String>) {
+        let (kind, diagnostics) = script_kind(&parser::parse(src).unwrap(), Some(Path::new(path)));
+
+        (
+            kind,
+            diagnostics
+                .into_iter()
+                .map(|diagnostic| diagnostic.message)
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn kinds_come_from_annotations_or_file_names() {
+        assert_eq!(
+            kind("@server\nprint(1)", "main.cop"),
+            (Some(ScriptKind::Server), vec![])
+        );
+        assert_eq!(
+            kind("print(1)", "input.client.cop"),
+            (Some(ScriptKind::Client), vec![])
+        );
+        assert_eq!(
+            kind("print(1)", "util.module.cop"),
+            (Some(ScriptKind::Module), vec![])
+        );
+        assert_eq!(kind("print(1)", "main.cop"), (None, vec![]));
+        assert_eq!(
+            kind("type Id = number\n@module\nprint(1)", "main.cop"),
+            (Some(ScriptKind::Module), vec![])
+        );
+    }
+
+    #[test]
+    fn annotations_must_be_known_consistent_and_first() {
+        assert_eq!(
+            kind("print(1)\n@server\n@nope", "main.cop").1,
+            [
+                "'@server' has to come before any code",
+                "unknown annotation '@nope', expected '@server', '@client' or '@module'",
+            ]
+        );
+        assert_eq!(
+            kind("@server\n@client", "main.cop").1,
+            ["a file can only be annotated with one script kind"]
+        );
+        assert_eq!(
+            kind("@client", "main.server.cop").1,
+            ["'@client' doesn't match the file name, which says it's a server script"]
+        );
+    }
+
+    #[test]
+    fn warns_about_apis_of_the_other_side() {
+        let tokens = backend::tokens(
+            r#"let players = game:GetService("Players")
+print(players.LocalPlayer)
+\\
+local store = game:GetService("DataStoreService")
+\\
+"#,
+        )
+        .unwrap();
+        let apis = |apis: &[&str]| apis.iter().map(|api| api.to_string()).collect::<Vec<_>>();
+        let warnings = |kind| {
+            check_apis(&tokens, kind, &apis(&CLIENT_APIS), &apis(&SERVER_APIS))
+                .into_iter()
+                .map(|diagnostic| diagnostic.message)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            warnings(ScriptKind::Server),
+            ["'LocalPlayer' only works in client scripts, but this is a server script"]
+        );
+        assert_eq!(
+            warnings(ScriptKind::Client),
+            ["'DataStoreService' only works in server scripts, but this is a client script"]
+        );
+        assert!(warnings(ScriptKind::Module).is_empty());
+    }
+}
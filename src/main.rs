@@ -1,19 +1,304 @@
-use std::{env::args, fs, path::PathBuf};
+use std::{
+    env,
+    fmt::Display,
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    process, thread,
+    time::Duration,
+};
+
+use crate::{
+    cli::{Cli, Command, USAGE},
+    config::Config,
+    frontend::formatter,
+    project::{output_file_name, Build, Watch},
+    util::diagnostic::Diagnostic,
+};
 
 pub mod backend;
+pub mod bundle;
+pub mod cache;
+pub mod cli;
+pub mod config;
 pub mod frontend;
+pub mod project;
+pub mod rojo;
 pub mod util;
 
-fn main() {
-    let args = args().collect::<Vec<_>>();
-    let src = fs::read(&args[1]).expect("Failed to read file");
+fn fail(message: impl Display) -> ! {
+    eprintln!("error: {message}");
+    process::exit(1);
+}
+
+/// Reads the file given on the command line, or stdin for `-`.
+fn read_input(input: &str) -> (String, Option<PathBuf>) {
+    if input == "-" {
+        let mut src = String::new();
+
+        io::stdin()
+            .read_to_string(&mut src)
+            .unwrap_or_else(|err| fail(format!("can't read stdin: {err}")));
+
+        return (src, None);
+    }
+
+    match fs::read_to_string(input) {
+        Ok(src) => (src, Some(input.into())),
+        Err(err) => fail(format!("can't read '{input}': {err}")),
+    }
+}
+
+fn print_diagnostics(path: Option<&Path>, diagnostics: &[Diagnostic], quiet: bool) {
+    let cwd = env::current_dir().unwrap_or_default();
+
+    for diagnostic in diagnostics {
+        if quiet && !diagnostic.is_error() {
+            continue;
+        }
+
+        match path {
+            Some(path) => {
+                let path = path.strip_prefix(&cwd).unwrap_or(path);
+
+                eprintln!("{}: {diagnostic}", path.display());
+            }
+            None => eprintln!("{diagnostic}"),
+        }
+    }
+}
+
+/// Writes the output of a single file to `-o`, to stdout for `--stdout` or
+/// stdin input, and to `default` otherwise.
+fn write_output(cli: &Cli, default: Option<PathBuf>, out: &str) {
+    let path = match (&cli.out, default) {
+        (Some(path), _) if !cli.stdout => path.clone(),
+        (None, Some(path)) if !cli.stdout => path,
+        _ => return print!("{out}"),
+    };
+
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent).unwrap_or_else(|err| fail(err));
+    }
+
+    fs::write(&path, out)
+        .unwrap_or_else(|err| fail(format!("can't write '{}': {err}", path.display())));
+}
+
+fn report(build: &Build, quiet: bool, summary: String) {
+    for (path, diagnostic) in &build.diagnostics {
+        print_diagnostics(Some(path), std::slice::from_ref(diagnostic), quiet);
+    }
+
+    if !quiet {
+        println!("{summary}");
+    }
+}
+
+fn build_summary(build: &Build) -> String {
+    format!(
+        "compiled {} file(s), {} unchanged, copied {}, removed {}",
+        build.compiled, build.cached, build.copied, build.removed
+    )
+}
+
+fn build(cli: &Cli, config: &Config) {
+    let options = config.options();
+    let mut project = config.project();
+
+    let Some(input) = &cli.input else {
+        if cli.stdout {
+            fail("'--stdout' needs a file to build");
+        }
+
+        if let Some(out) = &cli.out {
+            project.out = out.clone();
+        }
+
+        project.validate().unwrap_or_else(|err| fail(err));
+
+        let build = project.build(&options);
+        report(&build, cli.quiet, build_summary(&build));
+
+        if build.has_errors() {
+            process::exit(1);
+        }
+
+        return;
+    };
+
+    if cli.bundle {
+        return build_bundle(cli, config, input);
+    }
+
+    let (src, path) = read_input(input);
+    let compiled = backend::compile(&src, path.as_deref(), &options);
+
+    print_diagnostics(path.as_deref(), &compiled.diagnostics, cli.quiet);
+
+    if compiled
+        .diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.is_error())
+    {
+        process::exit(1);
+    }
+
+    let default = path.map(|path| {
+        project
+            .out
+            .join(output_file_name(&path, compiled.kind, &project.extension))
+    });
+
+    write_output(cli, default, &project.with_header(compiled.luau));
+}
+
+fn build_bundle(cli: &Cli, config: &Config, input: &str) {
+    let path = Path::new(input);
 
-    let out = backend::gen(&String::from_utf8(src).unwrap());
-    let dist_dir = PathBuf::from("dist/");
+    if !path.is_file() {
+        fail(format!("can't read '{input}'"));
+    }
+
+    let project = config.project();
+    let bundle = bundle::bundle(path, config.options()).unwrap_or_else(|err| fail(err));
+
+    for (path, diagnostic) in &bundle.diagnostics {
+        print_diagnostics(Some(path), std::slice::from_ref(diagnostic), cli.quiet);
+    }
 
-    if !dist_dir.exists() {
-        fs::create_dir_all(&dist_dir).expect("Failed to create dist directory");
+    if bundle.has_errors() {
+        process::exit(1);
     }
 
-    fs::write(dist_dir.join("out.luau"), out).expect("Failed to write");
+    let default = project
+        .out
+        .join(output_file_name(path, bundle.kind, &project.extension));
+
+    write_output(cli, Some(default), &project.with_header(bundle.luau));
+}
+
+fn check(cli: &Cli, config: &Config) {
+    let options = config.options();
+
+    let Some(input) = &cli.input else {
+        let build = config.project().check(&options);
+        report(
+            &build,
+            cli.quiet,
+            format!("checked {} file(s)", build.compiled),
+        );
+
+        if build.has_errors() {
+            process::exit(1);
+        }
+
+        return;
+    };
+
+    let (src, path) = read_input(input);
+    let (_, diagnostics) = backend::gen_file(&src, path.as_deref(), &options);
+
+    print_diagnostics(path.as_deref(), &diagnostics, cli.quiet);
+
+    if diagnostics.iter().any(|diagnostic| diagnostic.is_error()) {
+        process::exit(1);
+    }
+}
+
+fn fmt(cli: &Cli, config: &Config) {
+    let Some(input) = &cli.input else {
+        let mut formatted = 0;
+
+        for path in config.project().sources() {
+            let src = fs::read_to_string(&path).unwrap_or_else(|err| fail(err));
+            let out = formatter::format(&src);
+
+            if out != src {
+                fs::write(&path, out).unwrap_or_else(|err| fail(err));
+                formatted += 1;
+            }
+        }
+
+        if !cli.quiet {
+            println!("formatted {formatted} file(s)");
+        }
+
+        return;
+    };
+
+    let (src, path) = read_input(input);
+
+    write_output(cli, path, &formatter::format(&src));
+}
+
+fn watch(cli: &Cli, config: &Config) {
+    let options = config.options();
+    let mut project = config.project();
+    let mut watch = Watch::default();
+
+    if let Some(out) = &cli.out {
+        project.out = out.clone();
+    }
+
+    project.validate().unwrap_or_else(|err| fail(err));
+
+    loop {
+        if let Some(build) = watch.poll(&project, &options) {
+            report(&build, cli.quiet, build_summary(&build));
+        }
+
+        thread::sleep(Duration::from_millis(500));
+    }
+}
+
+fn emit(cli: &Cli, config: &Config) {
+    let (src, path) = read_input(cli.input.as_deref().unwrap());
+
+    let out = if cli.command == Command::EmitTokens {
+        let tokens = backend::tokens(&src).unwrap_or_else(|err| fail(err));
+
+        format!("{tokens:#?}\n")
+    } else {
+        let (ast, diagnostics) = backend::analyze(&src, path.as_deref(), &config.options());
+
+        print_diagnostics(path.as_deref(), &diagnostics, cli.quiet);
+
+        format!("{ast:#?}\n")
+    };
+
+    write_output(cli, None, &out);
+}
+
+fn main() {
+    if env::args().len() == 1 {
+        eprint!("{USAGE}");
+        process::exit(2);
+    }
+
+    let cli = match Cli::parse(env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(err) => {
+            eprintln!("error: {err}\nRun 'copiler --help' to see the available commands");
+            process::exit(2);
+        }
+    };
+
+    let config = match cli.command {
+        Command::Help => return print!("{USAGE}"),
+        Command::Version => return println!("copiler {}", env!("CARGO_PKG_VERSION")),
+        _ => Config::discover(&env::current_dir().unwrap()).unwrap_or_else(|err| fail(err)),
+    };
+
+    match cli.command {
+        Command::Build => build(&cli, &config),
+        Command::Check => check(&cli, &config),
+        Command::Fmt => fmt(&cli, &config),
+        Command::Watch => watch(&cli, &config),
+        Command::EmitAst | Command::EmitTokens => emit(&cli, &config),
+        Command::Help | Command::Version => unreachable!(),
+    }
 }
@@ -0,0 +1,867 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fs, panic,
+    path::{self, Component, Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::SystemTime,
+};
+
+use crate::{
+    backend::{self, Options},
+    cache::{self, Cache, Entry},
+    frontend::{
+        modules::{self, Signature},
+        scripts::ScriptKind,
+    },
+    util::diagnostic::Diagnostic,
+};
+
+/// A source tree that's compiled into a mirrored tree of Luau files.
+pub struct Project {
+    pub src: PathBuf,
+    pub out: PathBuf,
+    /// A comment written at the top of every compiled file
+    pub header: Option<String>,
+    /// The extension of compiled files
+    pub extension: String,
+    /// Identifies the settings outputs were compiled with, so the cache is
+    /// dropped when they change
+    pub fingerprint: u64,
+}
+
+/// What a build did, and the diagnostics of every file it compiled.
+#[derive(Default)]
+pub struct Build {
+    pub compiled: usize,
+    /// Files that were up to date in the cache
+    pub cached: usize,
+    pub copied: usize,
+    pub removed: usize,
+    pub diagnostics: Vec<(PathBuf, Diagnostic)>,
+}
+
+impl Build {
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|(_, diagnostic)| diagnostic.is_error())
+    }
+}
+
+/// Collects every file under `dir`, sorted so builds are deterministic.
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    let mut entries = entries
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            walk(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+/// An absolute path with `.` and `..` resolved, without touching the file
+/// system since the output directory might not exist yet.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path::absolute(path).unwrap_or(path.into()).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+/// Maps `items` on every core, returning the results in the same order.
+fn parallel<T: Sync, R: Send>(items: &[T], map: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let threads = thread::available_parallelism()
+        .map_or(1, |threads| threads.get())
+        .min(items.len());
+    let next = AtomicUsize::new(0);
+
+    let mut results = thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = vec![];
+
+                    // Each worker takes the next item until there are none
+                    // left, so a slow file doesn't hold up the others
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            break results;
+                        };
+
+                        results.push((index, map(item)));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|err| panic::resume_unwind(err))
+            })
+            .collect::<Vec<_>>()
+    });
+
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// The name of the output of a `.cop` file. Script kinds get the suffixes
+/// Rojo expects, `main.cop` annotated with `@server` is `main.server.luau`.
+pub fn output_file_name(path: &Path, kind: Option<ScriptKind>, extension: &str) -> String {
+    let stem = path.file_stem().unwrap().to_string_lossy();
+    let name = stem.strip_suffix(".module").unwrap_or(&stem);
+
+    match kind {
+        Some(kind @ (ScriptKind::Server | ScriptKind::Client))
+            if ScriptKind::from_path(path).is_none() =>
+        {
+            format!("{name}.{}.{extension}", kind.name())
+        }
+        _ => format!("{name}.{extension}"),
+    }
+}
+
+fn is_cop(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "cop")
+}
+
+fn is_luau(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "luau" || extension == "lua")
+}
+
+impl Project {
+    pub fn new(src: impl Into<PathBuf>, out: impl Into<PathBuf>) -> Self {
+        Self {
+            src: src.into(),
+            out: out.into(),
+            header: None,
+            extension: "luau".into(),
+            fingerprint: cache::hash(env!("CARGO_PKG_VERSION")),
+        }
+    }
+
+    /// Makes sure outputs can't overwrite sources, or be taken for them by
+    /// the next build.
+    pub fn validate(&self) -> Result<(), String> {
+        let src = normalize(&self.src);
+        let out = normalize(&self.out);
+
+        if out.starts_with(&src) || src.starts_with(&out) {
+            return Err(format!(
+                "the output directory '{}' can't be, contain or be inside the source directory '{}'",
+                self.out.display(),
+                self.src.display()
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Puts the configured header comment above compiled code.
+    pub fn with_header(&self, luau: String) -> String {
+        match &self.header {
+            Some(header) => {
+                let header = header
+                    .lines()
+                    .map(|line| format!("-- {line}\n"))
+                    .collect::<String>();
+
+                header + &luau
+            }
+            None => luau,
+        }
+    }
+
+    /// Every `.cop` file of the project.
+    pub fn sources(&self) -> Vec<PathBuf> {
+        let mut files = vec![];
+        walk(&self.src, &mut files);
+
+        files.into_iter().filter(|path| is_cop(path)).collect()
+    }
+
+    /// When each file of the project was last modified, to tell when the
+    /// project has to be rebuilt.
+    pub fn snapshot(&self) -> HashMap<PathBuf, SystemTime> {
+        let mut files = vec![];
+        walk(&self.src, &mut files);
+
+        files
+            .into_iter()
+            .filter_map(|path| {
+                let modified = fs::metadata(&path).and_then(|metadata| metadata.modified());
+
+                Some((path, modified.ok()?))
+            })
+            .collect()
+    }
+
+    /// Reports the diagnostics of every `.cop` file without writing anything.
+    pub fn check(&self, options: &Options) -> Build {
+        let mut build = Build::default();
+        let sources = self.sources();
+
+        let diagnostics = parallel(&sources, |path| {
+            let src = fs::read_to_string(path).expect("Failed to read file");
+
+            backend::compile(&src, Some(path), options).diagnostics
+        });
+
+        for (path, diagnostics) in sources.iter().zip(diagnostics) {
+            build.compiled += 1;
+            build.diagnostics.extend(
+                diagnostics
+                    .into_iter()
+                    .map(|diagnostic| (path.clone(), diagnostic)),
+            );
+        }
+
+        build
+    }
+
+    /// Where the output of a source file is written, `.cop` files are named
+    /// after the kind of script they compile to.
+    pub fn output(&self, path: &Path, kind: Option<ScriptKind>) -> PathBuf {
+        let out = self.out.join(path.strip_prefix(&self.src).unwrap());
+
+        if is_cop(path) {
+            out.with_file_name(output_file_name(path, kind, &self.extension))
+        } else {
+            out
+        }
+    }
+
+    /// Compiles a `.cop` file and writes its output unless it has errors.
+    fn compile(&self, path: &Path, src: &str, options: &Options) -> Entry {
+        let dir = path.parent().unwrap();
+        let compiled = backend::compile(src, Some(path), options);
+        let failed = compiled
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.is_error());
+
+        if !failed {
+            self.write(&self.output(path, compiled.kind), &compiled.luau);
+        }
+
+        let dependencies = compiled
+            .imports
+            .iter()
+            .map(|import| {
+                let dependency = modules::resolve_import(dir, import);
+                let signature = modules::read_signature(&dependency).ok();
+
+                (dependency, signature)
+            })
+            .collect();
+
+        Entry {
+            hash: cache::hash(src),
+            luau: (!failed).then_some(compiled.luau),
+            signature: compiled.exports.map(|exports| Signature {
+                exports,
+                kind: compiled.kind,
+            }),
+            kind: compiled.kind,
+            dependencies,
+            diagnostics: compiled.diagnostics.iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Compiles files in parallel and records them in the cache, in the
+    /// order they were given.
+    fn compile_all(
+        &self,
+        files: &[(&PathBuf, String)],
+        options: &Options,
+        cache: &mut Cache,
+        build: &mut Build,
+    ) {
+        let entries = parallel(files, |(path, src)| self.compile(path, src, options));
+
+        for ((path, _), mut entry) in files.iter().zip(entries) {
+            // A file that failed keeps its last output, under the name it had
+            if let Some(old) = cache.files.get(*path).filter(|_| entry.luau.is_none()) {
+                entry.kind = old.kind;
+            }
+
+            if entry.luau.is_some() {
+                build.compiled += 1;
+                cache.outputs.insert(self.output(path, entry.kind));
+            }
+
+            build.diagnostics.extend(
+                entry
+                    .diagnostics
+                    .iter()
+                    .map(|diagnostic| ((*path).clone(), diagnostic.into())),
+            );
+
+            // A file whose kind changed was written to a new name
+            if let Some(old) = cache.files.get(*path).filter(|old| old.kind != entry.kind) {
+                let old = self.output(path, old.kind);
+
+                self.remove_output(&old, cache, build);
+            }
+
+            cache.files.insert((*path).clone(), entry);
+        }
+    }
+
+    fn write(&self, out: &Path, luau: &str) {
+        fs::create_dir_all(out.parent().unwrap()).expect("Failed to create output directory");
+        fs::write(out, self.with_header(luau.to_string())).expect("Failed to write");
+    }
+
+    fn copy(&self, path: &Path, cache: &mut Cache, build: &mut Build) {
+        let out = self.output(path, None);
+
+        fs::create_dir_all(out.parent().unwrap()).expect("Failed to create output directory");
+        fs::copy(path, &out).expect("Failed to copy");
+
+        cache.outputs.insert(out);
+        build.copied += 1;
+    }
+
+    /// Removes a file an earlier build wrote, along with the directories it
+    /// leaves empty. Files the builds didn't write are never touched.
+    fn remove_output(&self, path: &Path, cache: &mut Cache, build: &mut Build) {
+        if !cache.outputs.remove(path) || fs::remove_file(path).is_err() {
+            return;
+        }
+
+        build.removed += 1;
+
+        for dir in path.ancestors().skip(1).take_while(|dir| *dir != self.out) {
+            // Fails when the directory still has files in it
+            if fs::remove_dir(dir).is_err() {
+                break;
+            }
+        }
+    }
+
+    /// Brings the outputs of the given `.cop` files up to date. A file is
+    /// only compiled again when its source changed, or when a file it
+    /// imports now exports something else or is another kind of script.
+    fn update(&self, paths: &[PathBuf], options: &Options, cache: &mut Cache, build: &mut Build) {
+        let (unchanged, changed): (Vec<_>, Vec<_>) = paths
+            .iter()
+            .map(|path| (path, fs::read_to_string(path).expect("Failed to read file")))
+            .partition(|(path, src)| {
+                cache
+                    .files
+                    .get(*path)
+                    .is_some_and(|entry| entry.hash == cache::hash(src))
+            });
+
+        // Changed files go first, so the signature of every file is current
+        // when the files importing them are looked at
+        self.compile_all(&changed, options, cache, build);
+
+        let mut stale = vec![];
+
+        for (path, src) in unchanged {
+            let entry = &cache.files[path];
+            let fresh = entry.dependencies.iter().all(|(dependency, signature)| {
+                let current = match cache.files.get(dependency) {
+                    Some(entry) => entry.signature.clone(),
+                    None => modules::read_signature(dependency).ok(),
+                };
+
+                current == *signature
+            });
+
+            if !fresh {
+                stale.push((path, src));
+                continue;
+            }
+
+            let out = self.output(path, entry.kind);
+
+            if let Some(luau) = &entry.luau {
+                if !out.exists() {
+                    self.write(&out, luau);
+                }
+
+                cache.outputs.insert(out);
+            }
+
+            build.cached += 1;
+            build.diagnostics.extend(
+                entry
+                    .diagnostics
+                    .iter()
+                    .map(|diagnostic| (path.clone(), diagnostic.into())),
+            );
+        }
+
+        self.compile_all(&stale, options, cache, build);
+
+        // Stable, so each file keeps the order of its own diagnostics
+        build.diagnostics.sort_by(|(a, _), (b, _)| a.cmp(b));
+    }
+
+    /// Compiles every `.cop` file to a `.luau` file at the same path under
+    /// the output directory, copies Luau files through as they are and
+    /// removes the outputs of earlier builds whose source no longer exists. Files that didn't
+    /// change since the last build are taken from the cache.
+    pub fn build(&self, options: &Options) -> Build {
+        let mut cache = Cache::load(&self.out, self.fingerprint);
+        let build = self.build_with(options, &mut cache);
+
+        cache.save(&self.out);
+
+        build
+    }
+
+    fn build_with(&self, options: &Options, cache: &mut Cache) -> Build {
+        let mut build = Build::default();
+        let mut files = vec![];
+
+        walk(&self.src, &mut files);
+
+        let sources = files
+            .iter()
+            .filter(|path| is_cop(path))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        cache.files.retain(|path, _| sources.contains(path));
+        self.update(&sources, options, cache, &mut build);
+
+        for path in &files {
+            if is_luau(path) {
+                self.copy(path, cache, &mut build);
+            }
+        }
+
+        let outputs = files
+            .iter()
+            .filter(|path| is_cop(path) || is_luau(path))
+            .map(|path| self.output(path, cache.files.get(path).and_then(|entry| entry.kind)))
+            .collect::<HashSet<_>>();
+
+        let stale = cache
+            .outputs
+            .iter()
+            .filter(|path| !outputs.contains(*path))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for path in stale {
+            self.remove_output(&path, cache, &mut build);
+        }
+
+        build
+    }
+}
+
+/// Rebuilds a project as its files change. Only the files that changed and
+/// the files importing them are looked at again.
+#[derive(Default)]
+pub struct Watch {
+    snapshot: HashMap<PathBuf, SystemTime>,
+    cache: Option<Cache>,
+}
+
+impl Watch {
+    /// Rebuilds what changed since the last call, `None` when nothing did.
+    /// The first call builds the whole project.
+    pub fn poll(&mut self, project: &Project, options: &Options) -> Option<Build> {
+        let snapshot = project.snapshot();
+
+        let Some(cache) = &mut self.cache else {
+            let mut cache = Cache::load(&project.out, project.fingerprint);
+            let build = project.build_with(options, &mut cache);
+
+            cache.save(&project.out);
+
+            self.cache = Some(cache);
+            self.snapshot = snapshot;
+
+            return Some(build);
+        };
+
+        if snapshot == self.snapshot {
+            return None;
+        }
+
+        let changed = snapshot
+            .iter()
+            .filter(|(path, modified)| self.snapshot.get(*path) != Some(modified))
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+        let removed = self
+            .snapshot
+            .keys()
+            .filter(|path| !snapshot.contains_key(*path))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let mut build = Build::default();
+
+        for path in &removed {
+            let kind = cache.files.remove(path).and_then(|entry| entry.kind);
+
+            if is_cop(path) || is_luau(path) {
+                project.remove_output(&project.output(path, kind), cache, &mut build);
+            }
+        }
+
+        // The files importing a changed or removed file are looked at again,
+        // its signature might be different now
+        let mut dirty = BTreeSet::new();
+
+        for path in changed.iter().chain(&removed) {
+            if is_cop(path) && snapshot.contains_key(path) {
+                dirty.insert(path.clone());
+            }
+
+            dirty.extend(
+                cache
+                    .files
+                    .iter()
+                    .filter(|(_, entry)| entry.dependencies.contains_key(path))
+                    .map(|(importer, _)| importer.clone()),
+            );
+        }
+
+        for path in &changed {
+            if is_luau(path) {
+                project.copy(path, cache, &mut build);
+            }
+        }
+
+        project.update(
+            &dirty.into_iter().collect::<Vec<_>>(),
+            options,
+            cache,
+            &mut build,
+        );
+        cache.save(&project.out);
+
+        self.snapshot = snapshot;
+
+        Some(build)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process, time::Duration};
+
+    use super::*;
+
+    /// An empty project in a directory of its own.
+    fn project(name: &str) -> Project {
+        let dir = env::temp_dir().join(format!("copiler-{name}-{}", process::id()));
+
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+
+        Project {
+            src: dir.join("src"),
+            out: dir.join("out"),
+            header: None,
+            extension: "luau".into(),
+            fingerprint: 0,
+        }
+    }
+
+    fn write(project: &Project, file: &str, src: &str) -> PathBuf {
+        let path = project.src.join(file);
+
+        fs::write(&path, src).unwrap();
+
+        path
+    }
+
+    fn errors(build: &Build) -> Vec<(String, String)> {
+        build
+            .diagnostics
+            .iter()
+            .filter(|(_, diagnostic)| diagnostic.is_error())
+            .map(|(path, diagnostic)| {
+                let file = path.file_name().unwrap().to_string_lossy();
+
+                (file.into_owned(), diagnostic.message.clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn syntax_errors_are_reported_against_their_file() {
+        let project = project("syntax-errors");
+        let options = Options::default();
+        let mut cache = Cache::default();
+
+        write(&project, "a.cop", "import { x } from \"./b\"\nprint(x)\n");
+        write(&project, "b.cop", "@module\nexport let x = (\n");
+
+        let build = project.build_with(&options, &mut cache);
+
+        assert_eq!(
+            errors(&build),
+            [("b.cop".into(), "Expected expression after '('".into())]
+        );
+
+        write(&project, "b.cop", "@module\nexport let x = 1\n");
+
+        let build = project.build_with(&options, &mut cache);
+
+        assert!(errors(&build).is_empty());
+        assert_eq!(build.compiled, 2);
+    }
+
+    #[test]
+    fn files_with_syntax_errors_depend_on_their_imports() {
+        let project = project("broken-importer");
+        let options = Options::default();
+        let mut cache = Cache::default();
+
+        let a = write(&project, "a.cop", "import { x } from \"./b\"\nlet y = (\n");
+        let b = write(&project, "b.cop", "@module\nexport let x = 1\n");
+
+        project.build_with(&options, &mut cache);
+
+        assert!(cache.files[&a].dependencies.contains_key(&b));
+    }
+
+    #[test]
+    fn importers_are_checked_again_when_a_module_changes_kind() {
+        let project = project("kind-change");
+        let options = Options::default();
+        let mut cache = Cache::default();
+
+        write(&project, "a.cop", "import { x } from \"./b\"\nprint(x)\n");
+        write(&project, "b.cop", "@server\nexport let x = 1\n");
+
+        let build = project.build_with(&options, &mut cache);
+
+        assert!(errors(&build).contains(&(
+            "a.cop".into(),
+            "'./b' is a server script, only modules can be imported".into()
+        )));
+
+        write(&project, "b.cop", "@module\nexport let x = 1\n");
+
+        let build = project.build_with(&options, &mut cache);
+
+        assert!(errors(&build).is_empty());
+        assert_eq!(build.compiled, 2);
+    }
+
+    #[test]
+    fn builds_mirror_the_source_tree() {
+        let project = project("mirror");
+        let options = Options::default();
+        let mut cache = Cache::default();
+
+        fs::create_dir_all(project.src.join("shared")).unwrap();
+        write(&project, "shared/util.cop", "print(1)\n");
+        write(&project, "lib.luau", "return {}\n");
+
+        let build = project.build_with(&options, &mut cache);
+
+        assert_eq!((build.compiled, build.copied), (1, 1));
+        assert!(project.out.join("shared/util.luau").is_file());
+        assert!(project.out.join("lib.luau").is_file());
+
+        fs::remove_file(project.src.join("shared/util.cop")).unwrap();
+
+        let build = project.build_with(&options, &mut cache);
+
+        assert_eq!(build.removed, 1);
+        assert!(!project.out.join("shared").exists());
+    }
+
+    #[test]
+    fn outputs_cant_overlap_sources() {
+        assert!(Project::new("src", "dist").validate().is_ok());
+        assert!(Project::new("src", "src/out").validate().is_err());
+        assert!(Project::new("game/src", "game").validate().is_err());
+        assert!(Project::new("src", "./src/").validate().is_err());
+    }
+
+    #[test]
+    fn headers_are_written_as_comments() {
+        let mut project = Project::new("src", "dist");
+
+        project.header = Some("Generated\ndon't edit".into());
+
+        assert_eq!(
+            project.with_header("print(1)\n".into()),
+            "-- Generated\n-- don't edit\nprint(1)\n"
+        );
+    }
+
+    /// Writes a file again with a later modification time, so a watch sees
+    /// it change even within the resolution of the file system's clock.
+    fn touch(project: &Project, file: &str, src: &str) {
+        let path = write(project, file, src);
+        let modified = fs::metadata(&path).unwrap().modified().unwrap();
+
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified + Duration::from_secs(1))
+            .unwrap();
+    }
+
+    #[test]
+    fn watches_rebuild_changed_files_and_their_importers() {
+        let project = project("watch");
+        let options = Options::default();
+        let mut watch = Watch::default();
+
+        write(&project, "a.cop", "import { x } from \"./b\"\nprint(x)\n");
+        write(&project, "b.cop", "@module\nexport let x = 1\n");
+        write(&project, "c.cop", "print(1)\n");
+
+        let build = watch.poll(&project, &options).unwrap();
+
+        assert_eq!(build.compiled, 3);
+        assert!(watch.poll(&project, &options).is_none());
+
+        touch(
+            &project,
+            "b.cop",
+            "@module\nexport let x = 1\nexport let y = 2\n",
+        );
+
+        let build = watch.poll(&project, &options).unwrap();
+
+        assert_eq!((build.compiled, build.cached), (2, 0));
+
+        fs::remove_file(project.src.join("c.cop")).unwrap();
+
+        let build = watch.poll(&project, &options).unwrap();
+
+        assert_eq!((build.compiled, build.removed), (0, 1));
+        assert!(!project.out.join("c.luau").exists());
+    }
+
+    #[test]
+    fn unchanged_files_come_from_the_cache() {
+        let project = project("cache");
+        let options = Options::default();
+
+        write(&project, "a.cop", "let x = 1\nlet x = 2\n");
+        write(&project, "b.cop", "print(1)\n");
+
+        let build = project.build(&options);
+
+        assert_eq!((build.compiled, build.cached), (2, 0));
+
+        fs::remove_file(project.out.join("a.luau")).unwrap();
+        write(&project, "b.cop", "print(2)\n");
+
+        let build = project.build(&options);
+
+        // The warning of the cached file is reported again, and its deleted
+        // output written again
+        assert_eq!((build.compiled, build.cached), (1, 1));
+        assert_eq!(build.diagnostics.len(), 1);
+        assert!(project.out.join("a.luau").is_file());
+
+        let project = Project {
+            fingerprint: 1,
+            ..project
+        };
+
+        assert_eq!(project.build(&options).compiled, 2);
+    }
+
+    #[test]
+    fn parallel_results_keep_the_order_of_their_items() {
+        let items = (0..100).collect::<Vec<u64>>();
+
+        assert_eq!(
+            parallel(&items, |item| item * 2),
+            (0..200).step_by(2).collect::<Vec<_>>()
+        );
+        assert!(parallel(&[] as &[u64], |item| *item).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "file 3")]
+    fn parallel_panics_reach_the_caller() {
+        parallel(&[1, 2, 3, 4], |item| {
+            if *item == 3 {
+                panic!("file {item}");
+            }
+        });
+    }
+
+    #[test]
+    fn parallel_builds_match_the_files() {
+        let project = project("parallel");
+        let options = Options::default();
+
+        for index in 0..20 {
+            write(
+                &project,
+                &format!("m{index}.cop"),
+                &format!("print({index})\n"),
+            );
+        }
+
+        let build = project.build(&options);
+
+        assert_eq!(build.compiled, 20);
+
+        for index in 0..20 {
+            let luau = fs::read_to_string(project.out.join(format!("m{index}.luau"))).unwrap();
+
+            assert_eq!(luau, format!("local _ = print({index})\n"));
+        }
+    }
+
+    #[test]
+    fn outputs_are_named_after_their_script_kind() {
+        let name = |path: &str, kind| output_file_name(Path::new(path), kind, "luau");
+
+        assert_eq!(
+            name("main.cop", Some(ScriptKind::Server)),
+            "main.server.luau"
+        );
+        assert_eq!(
+            name("main.server.cop", Some(ScriptKind::Server)),
+            "main.server.luau"
+        );
+        assert_eq!(
+            name("input.client.cop", Some(ScriptKind::Client)),
+            "input.client.luau"
+        );
+        assert_eq!(
+            name("util.module.cop", Some(ScriptKind::Module)),
+            "util.luau"
+        );
+        assert_eq!(name("util.cop", None), "util.luau");
+    }
+}
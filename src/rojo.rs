@@ -0,0 +1,184 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+
+use crate::backend::index_instance;
+
+pub const FILE_NAME: &str = "default.project.json";
+
+/// A Rojo project file, which says where directories of the file system end
+/// up in the Roblox instance tree.
+#[derive(Debug, Clone)]
+pub struct Rojo {
+    /// Every `$path` of the tree and the instance it's synced to, deepest
+    /// paths first
+    mounts: Vec<(PathBuf, String)>,
+}
+
+/// Collects the `$path`s of a tree node and its children.
+fn mounts(node: &Value, instance: String, root: &Path, mounts: &mut Vec<(PathBuf, String)>) {
+    let Some(node) = node.as_object() else {
+        return;
+    };
+
+    // `{ "optional": "path" }` is a path that doesn't have to exist
+    let path = match node.get("$path") {
+        Some(Value::String(path)) => Some(path.as_str()),
+        Some(Value::Object(path)) => path.get("optional").and_then(Value::as_str),
+        _ => None,
+    };
+
+    if let Some(path) = path {
+        mounts.push((root.join(path), instance.clone()));
+    }
+
+    for (name, child) in node {
+        if name.starts_with('$') {
+            continue;
+        }
+
+        let mut instance = instance.clone();
+        index_instance(&mut instance, name);
+
+        self::mounts(child, instance, root, mounts);
+    }
+}
+
+impl Rojo {
+    /// Parses a project file whose paths are relative to `root`.
+    pub fn parse(src: &str, root: &Path) -> Result<Self, String> {
+        let project: Value = serde_json::from_str(src).map_err(|err| err.to_string())?;
+        let tree = project
+            .get("tree")
+            .ok_or("the project doesn't have a 'tree'")?;
+
+        let mut mounts = vec![];
+
+        // Only a place knows where its instances are, the scripts of a model
+        // can only require each other relative to themselves
+        if tree.get("$className").and_then(Value::as_str) == Some("DataModel") {
+            self::mounts(tree, "game".into(), root, &mut mounts);
+        }
+
+        mounts.sort_by_key(|(path, _)| std::cmp::Reverse(path.components().count()));
+
+        Ok(Self { mounts })
+    }
+
+    /// Reads `default.project.json` in `dir`, `None` when there isn't one.
+    pub fn discover(dir: &Path) -> Result<Option<Self>, String> {
+        let path = dir.join(FILE_NAME);
+
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let src = fs::read_to_string(&path).map_err(|err| err.to_string())?;
+
+        Self::parse(&src, dir)
+            .map(Some)
+            .map_err(|err| format!("{}: {err}", path.display()))
+    }
+
+    /// The instance a file is synced to, following Rojo's naming: the
+    /// extension and a `.server` or `.client` suffix are dropped, and an
+    /// `init` file becomes the directory it's in.
+    pub fn instance(&self, path: &Path) -> Option<String> {
+        let (mount, instance) = self
+            .mounts
+            .iter()
+            .find(|(mount, _)| path.starts_with(mount))?;
+
+        let mut instance = instance.clone();
+        let relative = path.strip_prefix(mount).unwrap();
+        let mut components = relative.iter().peekable();
+
+        while let Some(component) = components.next() {
+            let name = component.to_str()?;
+
+            if components.peek().is_some() {
+                index_instance(&mut instance, name);
+                continue;
+            }
+
+            let name = Path::new(name).file_stem()?.to_str()?;
+            let name = [".server", ".client"]
+                .iter()
+                .find_map(|suffix| name.strip_suffix(suffix))
+                .unwrap_or(name);
+
+            if name != "init" {
+                index_instance(&mut instance, name);
+            }
+        }
+
+        Some(instance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROJECT: &str = r#"{
+        "name": "game",
+        "tree": {
+            "$className": "DataModel",
+            "ReplicatedStorage": {
+                "Shared": { "$path": "dist/shared" },
+                "Shared Items": { "$path": { "optional": "dist/shared/items" } }
+            },
+            "ServerScriptService": {
+                "Server": { "$path": "dist/server" }
+            }
+        }
+    }"#;
+
+    #[test]
+    fn files_are_named_like_rojo_names_them() {
+        let rojo = Rojo::parse(PROJECT, Path::new("game")).unwrap();
+
+        assert_eq!(
+            rojo.instance(Path::new("game/dist/shared/vehicles.luau"))
+                .as_deref(),
+            Some("game.ReplicatedStorage.Shared.vehicles")
+        );
+        assert_eq!(
+            rojo.instance(Path::new("game/dist/server/main.server.luau"))
+                .as_deref(),
+            Some("game.ServerScriptService.Server.main")
+        );
+        assert_eq!(
+            rojo.instance(Path::new("game/dist/shared/weapons/init.luau"))
+                .as_deref(),
+            Some("game.ReplicatedStorage.Shared.weapons")
+        );
+        assert_eq!(rojo.instance(Path::new("game/dist/other.luau")), None);
+    }
+
+    #[test]
+    fn the_deepest_path_wins() {
+        let rojo = Rojo::parse(PROJECT, Path::new("game")).unwrap();
+
+        assert_eq!(
+            rojo.instance(Path::new("game/dist/shared/items/sword.luau"))
+                .as_deref(),
+            Some("game.ReplicatedStorage[\"Shared Items\"].sword")
+        );
+    }
+
+    #[test]
+    fn models_have_no_instance_paths() {
+        let rojo = Rojo::parse(
+            r#"{ "name": "lib", "tree": { "$path": "dist" } }"#,
+            Path::new("."),
+        )
+        .unwrap();
+
+        assert_eq!(rojo.instance(Path::new("dist/lib.luau")), None);
+        assert!(Rojo::parse(r#"{ "name": "lib" }"#, Path::new(".")).is_err());
+    }
+}
@@ -0,0 +1,133 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// How a lint is reported, overriding its default severity.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// The name of the lint that reported this, if its level can be configured
+    pub lint: Option<&'static str>,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+            lint: None,
+        }
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            message: message.into(),
+            lint: None,
+        }
+    }
+
+    pub fn with_lint(mut self, lint: &'static str) -> Self {
+        self.lint = Some(lint);
+        self
+    }
+
+    /// Applies a configured lint level, `None` when the lint is allowed.
+    pub fn with_level(mut self, level: LintLevel) -> Option<Self> {
+        self.severity = match level {
+            LintLevel::Allow => return None,
+            LintLevel::Warn => Severity::Warning,
+            LintLevel::Error => Severity::Error,
+        };
+
+        Some(self)
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+/// Collects the diagnostics of a pass, naming the declaration each one is in
+/// since diagnostics don't point at lines yet.
+#[derive(Default)]
+pub struct Reporter {
+    pub diagnostics: Vec<Diagnostic>,
+    /// The declarations being looked at, innermost last
+    context: Vec<String>,
+}
+
+impl Reporter {
+    pub fn report(&mut self, diagnostic: Diagnostic) {
+        let diagnostic = match self.context.last() {
+            Some(name) => Diagnostic {
+                message: format!("in '{name}': {}", diagnostic.message),
+                ..diagnostic
+            },
+            None => diagnostic,
+        };
+
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Starts looking at the declaration `name`, until `exit` is called.
+    pub fn enter(&mut self, name: impl Into<String>) {
+        self.context.push(name.into());
+    }
+
+    pub fn exit(&mut self) {
+        self.context.pop();
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.severity {
+            Severity::Warning => write!(f, "warning: {}", self.message),
+            Severity::Error => write!(f, "error: {}", self.message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_name_the_innermost_declaration() {
+        let mut reporter = Reporter::default();
+
+        reporter.report(Diagnostic::error("a"));
+        reporter.enter("Car");
+        reporter.enter("Car.drive");
+        reporter.report(Diagnostic::error("b"));
+        reporter.exit();
+        reporter.report(Diagnostic::warning("c").with_lint("shadowing"));
+        reporter.exit();
+
+        let messages = reporter
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.message.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(messages, ["a", "in 'Car.drive': b", "in 'Car': c"]);
+        assert_eq!(reporter.diagnostics[2].lint, Some("shadowing"));
+    }
+}